@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use crate::ast::funcoes::FunctionRegistry;
+use crate::ast::parser::Node;
+use crate::ast::valor::Value;
+
+/// Valores das variáveis e funções definidas disponíveis durante a
+/// avaliação, junto com as funções do [`FunctionRegistry`].
+///
+/// Variáveis e funções têm nomes independentes: `f = 2` e `f(x) = x` podem
+/// coexistir.
+///
+/// ```
+/// use operations::ast::ambiente::Environment;
+/// use operations::ast::valor::Value;
+///
+/// let ambiente = Environment::new().com("preco", 30).com("ativo", Value::Booleano(true));
+/// assert_eq!(ambiente.obter("preco"), Some(&Value::Numero(30)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Environment<N = i64> {
+    variaveis: HashMap<String, Value<N>>,
+    funcoes: HashMap<String, UserFunction<N>>,
+    registro: FunctionRegistry<N>,
+}
+
+/// Uma função definida no próprio programa, como `f(x) = x * x + 1`.
+///
+/// O corpo só enxerga os próprios parâmetros e as variáveis do ambiente;
+/// os parâmetros de quem a chamou não são visíveis.
+#[derive(Debug, Clone)]
+pub struct UserFunction<N = i64> {
+    parametros: Vec<String>,
+    corpo: Node<N>,
+}
+
+impl<N> UserFunction<N> {
+    pub fn new(parametros: Vec<String>, corpo: Node<N>) -> Self {
+        Self { parametros, corpo }
+    }
+
+    pub fn parametros(&self) -> &[String] {
+        &self.parametros
+    }
+
+    pub fn corpo(&self) -> &Node<N> {
+        &self.corpo
+    }
+}
+
+impl<N> Default for Environment<N> {
+    fn default() -> Self {
+        Self {
+            variaveis: HashMap::new(),
+            funcoes: HashMap::new(),
+            registro: FunctionRegistry::new(),
+        }
+    }
+}
+
+impl<N> Environment<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define a variável e devolve o ambiente, para montá-lo em uma única expressão.
+    pub fn com(mut self, nome: impl Into<String>, valor: impl Into<Value<N>>) -> Self {
+        self.definir(nome, valor);
+        self
+    }
+
+    /// Define a variável, devolvendo o valor anterior se ela já existia.
+    pub fn definir(&mut self, nome: impl Into<String>, valor: impl Into<Value<N>>) -> Option<Value<N>> {
+        self.variaveis.insert(nome.into(), valor.into())
+    }
+
+    pub fn obter(&self, nome: &str) -> Option<&Value<N>> {
+        self.variaveis.get(nome)
+    }
+
+    pub fn remover(&mut self, nome: &str) -> Option<Value<N>> {
+        self.variaveis.remove(nome)
+    }
+
+    /// Os nomes das variáveis definidas, em ordem alfabética.
+    pub fn nomes(&self) -> Vec<&str> {
+        let mut nomes: Vec<&str> = self.variaveis.keys().map(String::as_str).collect();
+        nomes.sort_unstable();
+        nomes
+    }
+
+    /// Define a função, devolvendo a definição anterior se ela já existia.
+    pub fn definir_funcao(&mut self, nome: impl Into<String>, funcao: UserFunction<N>) -> Option<UserFunction<N>> {
+        self.funcoes.insert(nome.into(), funcao)
+    }
+
+    pub fn funcao(&self, nome: &str) -> Option<&UserFunction<N>> {
+        self.funcoes.get(nome)
+    }
+
+    /// Os nomes das funções definidas, em ordem alfabética.
+    pub fn nomes_de_funcoes(&self) -> Vec<&str> {
+        let mut nomes: Vec<&str> = self.funcoes.keys().map(String::as_str).collect();
+        nomes.sort_unstable();
+        nomes
+    }
+
+    /// Troca as funções registradas e devolve o ambiente.
+    pub fn com_registro(mut self, registro: FunctionRegistry<N>) -> Self {
+        self.registro = registro;
+        self
+    }
+
+    pub fn registro(&self) -> &FunctionRegistry<N> {
+        &self.registro
+    }
+
+    pub fn registro_mut(&mut self) -> &mut FunctionRegistry<N> {
+        &mut self.registro
+    }
+}
@@ -0,0 +1,576 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::ast::ambiente::{Environment, UserFunction};
+use crate::ast::funcoes::{self, Arity};
+use crate::ast::lexer::{TextSpan, TokenKind};
+use crate::ast::parser::{Ast, Node, Statement};
+use crate::numero::racional::RationalFormat;
+use crate::ast::valor::{Value, ValueType};
+use crate::numero::{ErroAritmetico, Number};
+
+/// Erro produzido durante a avaliação de uma árvore.
+///
+/// O span aponta para a subexpressão cuja operação falhou.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    DivisaoPorZero { span: TextSpan },
+    Overflow { span: TextSpan },
+    ExpoenteNegativo { span: TextSpan },
+    ExpoenteFracionario { span: TextSpan },
+    LiteralDecimal { span: TextSpan },
+    ResultadoIndefinido { span: TextSpan },
+    ResultadoIrracional { span: TextSpan },
+    OperadorNaoSuportado { op: TokenKind, span: TextSpan },
+    VariavelIndefinida { nome: String, span: TextSpan },
+    FuncaoIndefinida { nome: String, span: TextSpan },
+    /// O span aponta para os argumentos excedentes ou, se faltarem
+    /// argumentos, para a chamada inteira.
+    AridadeIncorreta { nome: String, esperada: Arity, recebida: usize, span: TextSpan },
+    /// Chamadas de funções definidas se aninharam além do limite do
+    /// [`Evaluator`]; o span aponta para a chamada que excedeu o limite.
+    RecursaoExcessiva { nome: String, limite: usize, span: TextSpan },
+    /// Um número foi usado onde se esperava um booleano, ou o contrário;
+    /// o span aponta para o operando de tipo errado.
+    TipoIncorreto { esperado: ValueType, encontrado: ValueType, span: TextSpan },
+}
+
+impl EvalError {
+    pub fn span(&self) -> &TextSpan {
+        match self {
+            EvalError::DivisaoPorZero { span }
+            | EvalError::Overflow { span }
+            | EvalError::ExpoenteNegativo { span }
+            | EvalError::ExpoenteFracionario { span }
+            | EvalError::LiteralDecimal { span }
+            | EvalError::ResultadoIndefinido { span }
+            | EvalError::ResultadoIrracional { span }
+            | EvalError::OperadorNaoSuportado { span, .. }
+            | EvalError::VariavelIndefinida { span, .. }
+            | EvalError::FuncaoIndefinida { span, .. }
+            | EvalError::AridadeIncorreta { span, .. }
+            | EvalError::RecursaoExcessiva { span, .. }
+            | EvalError::TipoIncorreto { span, .. } => span,
+        }
+    }
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::DivisaoPorZero { .. } => write!(f, "divisão por zero"),
+            EvalError::Overflow { .. } => write!(f, "resultado fora do intervalo representável"),
+            EvalError::ExpoenteNegativo { .. } => write!(f, "expoente negativo em uma potência inteira"),
+            EvalError::ExpoenteFracionario { .. } => write!(f, "expoente fracionário em uma potência exata"),
+            EvalError::LiteralDecimal { .. } => write!(f, "número decimal em uma avaliação inteira"),
+            EvalError::ResultadoIndefinido { .. } => write!(f, "resultado indefinido"),
+            EvalError::ResultadoIrracional { .. } => write!(f, "resultado irracional em uma avaliação exata"),
+            EvalError::OperadorNaoSuportado { op, .. } => write!(f, "operador não suportado: '{}'", op),
+            EvalError::VariavelIndefinida { nome, .. } => write!(f, "variável indefinida '{}'", nome),
+            EvalError::FuncaoIndefinida { nome, .. } => write!(f, "função indefinida '{}'", nome),
+            EvalError::AridadeIncorreta { nome, esperada, recebida, .. } => {
+                write!(f, "a função '{}' espera {}, mas recebeu {}", nome, esperada, recebida)
+            }
+            EvalError::RecursaoExcessiva { nome, limite, .. } => {
+                write!(f, "recursão excessiva em '{}': mais de {} chamadas aninhadas", nome, limite)
+            }
+            EvalError::TipoIncorreto { esperado, encontrado, .. } => {
+                write!(f, "tipo incorreto: esperava {}, encontrou {}", esperado, encontrado)
+            }
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+/// Convenção de arredondamento usada por `/` e `%`.
+///
+/// As três convenções concordam quando os operandos são positivos e diferem
+/// quando algum deles é negativo. Para `-7` dividido por `2`:
+///
+/// | convenção  | `-7 / 2` | `-7 % 2` | `7 / -2` | `7 % -2` |
+/// |------------|----------|----------|----------|----------|
+/// | `Truncar`  | -3       | -1       | -3       | 1        |
+/// | `Piso`     | -4       | 1        | -4       | -1       |
+/// | `Euclides` | -4       | 1        | -3       | 1        |
+///
+/// Independentemente da convenção escolhida, `//` sempre arredonda para
+/// baixo (piso) e `mod` sempre devolve o resto euclidiano, que nunca é
+/// negativo.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// Quociente arredondado em direção a zero; o resto tem o sinal do
+    /// dividendo. É a convenção do Rust e do C.
+    #[default]
+    Truncar,
+    /// Quociente arredondado para baixo; o resto tem o sinal do divisor.
+    /// É a convenção do Python.
+    Piso,
+    /// O resto nunca é negativo e o quociente se ajusta a ele.
+    Euclides,
+}
+
+/// Configuração usada para avaliar uma árvore.
+///
+/// `Node::avaliar`, `Ast::avaliar` e `Ast::eval_step` usam a configuração
+/// padrão; crie um `Evaluator` para escolher outra.
+///
+/// O domínio numérico é o da árvore, escolhido ao montá-la com o
+/// [`Parser`](crate::ast::parser::Parser); o crate traz quatro:
+///
+/// - `i64`, o padrão, que rejeita literais decimais;
+/// - `f64`: os literais inteiros são promovidos a `f64` (de forma exata até
+///   2^53) e `10 / 4` vale `2.5`;
+/// - [`Rational`](crate::numero::racional::Rational), com frações exatas:
+///   `10 / 3 + 23` vale `79/3` e o literal `0.1` vale exatamente `1/10`;
+/// - [`BigInt`](crate::numero::inteiro::BigInt), com as mesmas regras do
+///   domínio `i64`, mas sem overflow nem limite para o tamanho dos literais.
+///
+/// Nos domínios real e racional, `/` é a divisão exata; `//`, `%` e `mod`
+/// seguem as mesmas convenções do domínio inteiro. Divisões por zero e
+/// resultados fora do domínio viram erros.
+#[derive(Debug, Clone)]
+pub struct Evaluator {
+    arredondamento: Rounding,
+    formato_racional: RationalFormat,
+    limite_de_recursao: usize,
+}
+
+/// Quantas chamadas de funções definidas podem estar em andamento ao mesmo
+/// tempo, por padrão.
+pub const LIMITE_DE_RECURSAO: usize = 100;
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Self {
+            arredondamento: Rounding::default(),
+            formato_racional: RationalFormat::default(),
+            limite_de_recursao: LIMITE_DE_RECURSAO,
+        }
+    }
+}
+
+impl Evaluator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn com_arredondamento(mut self, arredondamento: Rounding) -> Self {
+        self.arredondamento = arredondamento;
+        self
+    }
+
+    /// Escolhe como as frações aparecem nas expressões do rastro racional.
+    pub fn com_formato_racional(mut self, formato: RationalFormat) -> Self {
+        self.formato_racional = formato;
+        self
+    }
+
+    pub fn arredondamento(&self) -> Rounding {
+        self.arredondamento
+    }
+
+    pub fn formato_racional(&self) -> RationalFormat {
+        self.formato_racional
+    }
+
+    /// Limita quantas chamadas de funções definidas no programa podem estar
+    /// aninhadas; além dele, a avaliação falha com
+    /// [`EvalError::RecursaoExcessiva`].
+    ///
+    /// A avaliação não usa a pilha nativa para as chamadas em andamento, de
+    /// modo que qualquer limite é respeitado; um limite alto só custa memória.
+    pub fn com_limite_de_recursao(mut self, limite: usize) -> Self {
+        self.limite_de_recursao = limite;
+        self
+    }
+
+    pub fn limite_de_recursao(&self) -> usize {
+        self.limite_de_recursao
+    }
+
+    /// Avalia o nó sem imprimir nada, usando aritmética verificada; qualquer
+    /// variável na árvore resulta em [`EvalError::VariavelIndefinida`].
+    pub fn avaliar<N: Number>(&self, node: &Node<N>) -> Result<Value<N>, EvalError> {
+        self.avaliar_em(node, &Environment::new())
+    }
+
+    /// Avalia o nó buscando as variáveis e as funções definidas no ambiente.
+    pub fn avaliar_em<N: Number>(&self, node: &Node<N>, ambiente: &Environment<N>) -> Result<Value<N>, EvalError> {
+        self.avaliar_no(node, Escopo::global(ambiente))
+    }
+
+    // Avalia sem recursão: `tarefas` guarda o que falta fazer, `valores` os
+    // resultados das subexpressões já avaliadas e `escopos` as chamadas de
+    // funções definidas em andamento. Assim nem a profundidade da árvore nem a
+    // das chamadas dependem da pilha nativa
+    fn avaliar_no<'a, N: Number>(&self, raiz: &'a Node<N>, escopo: Escopo<'a, N>) -> Result<Value<N>, EvalError> {
+        let mut tarefas = vec![Tarefa::Avaliar(raiz)];
+        let mut valores = Vec::new();
+        let mut escopos = vec![escopo];
+        while let Some(tarefa) = tarefas.pop() {
+            let escopo = escopos.last().expect("o escopo inicial só sai no fim");
+            match tarefa {
+                Tarefa::Avaliar(node) => match node {
+                    Node::Numero { valor, .. } => valores.push(Value::Numero(valor.clone())),
+                    Node::Booleano { valor, .. } => valores.push(Value::Booleano(*valor)),
+                    Node::Variavel { nome, span } => valores.push(escopo.variavel(nome, span)?),
+                    Node::BinaryOp { left, .. } => {
+                        tarefas.push(Tarefa::Direita(node));
+                        tarefas.push(Tarefa::Avaliar(left));
+                    }
+                    Node::UnaryOp { operand, .. } => {
+                        tarefas.push(Tarefa::Unaria(node));
+                        tarefas.push(Tarefa::Avaliar(operand));
+                    }
+                    Node::Chamada { nome, args, span } => {
+                        verificar_chamada(nome, args, span, escopo.ambiente)?;
+                        tarefas.push(Tarefa::Chamar(node));
+                        // Os argumentos são avaliados da esquerda para a direita
+                        tarefas.extend(args.iter().rev().map(Tarefa::Avaliar));
+                    }
+                    Node::Condicional { condicao, .. } => {
+                        tarefas.push(Tarefa::Ramo(node));
+                        tarefas.push(Tarefa::Avaliar(condicao));
+                    }
+                },
+                Tarefa::Direita(node) => {
+                    let Node::BinaryOp { op, left, right, .. } = node else {
+                        unreachable!("apenas operações binárias têm lado direito")
+                    };
+                    let esquerda = valores.last().expect("o lado esquerdo foi avaliado");
+                    // O lado direito de `&&` e `||` só é avaliado se decidir o resultado
+                    match curto_circuito(op, esquerda, left.span())? {
+                        Some(resultado) => *valores.last_mut().expect("o lado esquerdo foi avaliado") = resultado,
+                        None => {
+                            tarefas.push(Tarefa::Binaria(node));
+                            tarefas.push(Tarefa::Avaliar(right));
+                        }
+                    }
+                }
+                Tarefa::Binaria(node) => {
+                    let direita = valores.pop().expect("o lado direito foi avaliado");
+                    let esquerda = valores.pop().expect("o lado esquerdo foi avaliado");
+                    valores.push(self.aplicar_binario(node, &esquerda, &direita)?);
+                }
+                Tarefa::Unaria(node) => {
+                    let Node::UnaryOp { op, span, .. } = node else {
+                        unreachable!("apenas operações unárias têm um operando")
+                    };
+                    let valor = valores.pop().expect("o operando foi avaliado");
+                    valores.push(aplicar_unario(op, &valor, span)?);
+                }
+                Tarefa::Chamar(node) => {
+                    let Node::Chamada { nome, args, span } = node else {
+                        unreachable!("apenas chamadas têm argumentos")
+                    };
+                    let argumentos = valores.split_off(valores.len() - args.len());
+                    match self.invocar(nome, args, argumentos, span, escopo)? {
+                        Invocacao::Valor(valor) => valores.push(valor),
+                        Invocacao::Corpo(corpo, escopo) => {
+                            escopos.push(escopo);
+                            tarefas.push(Tarefa::Retornar);
+                            tarefas.push(Tarefa::Avaliar(corpo));
+                        }
+                    }
+                }
+                // Só o ramo escolhido pela condição é avaliado
+                Tarefa::Ramo(node) => {
+                    let Node::Condicional { condicao, entao, senao, .. } = node else {
+                        unreachable!("apenas condicionais têm ramos")
+                    };
+                    let escolha = valores.pop().expect("a condição foi avaliada");
+                    tarefas.push(Tarefa::Avaliar(if escolha.como_booleano(condicao.span())? { entao } else { senao }));
+                }
+                Tarefa::Retornar => {
+                    escopos.pop();
+                }
+            }
+        }
+        Ok(valores.pop().expect("a avaliação deixa o valor da raiz"))
+    }
+
+    // Aplica uma função a argumentos já avaliados, avaliando por inteiro o
+    // corpo de uma função definida
+    pub(crate) fn chamar<N: Number>(
+        &self,
+        nome: &str,
+        args: &[Node<N>],
+        valores: Vec<Value<N>>,
+        span: &TextSpan,
+        ambiente: &Environment<N>,
+    ) -> Result<Value<N>, EvalError> {
+        match self.invocar(nome, args, valores, span, &Escopo::global(ambiente))? {
+            Invocacao::Valor(valor) => Ok(valor),
+            Invocacao::Corpo(corpo, escopo) => self.avaliar_no(corpo, escopo),
+        }
+    }
+
+    // Começa uma chamada com argumentos já avaliados; as funções definidas no
+    // programa têm prioridade sobre as registradas, e estas sobre as
+    // embutidas, que só aceitam números e já devolvem o resultado
+    fn invocar<'a, N: Number>(
+        &self,
+        nome: &str,
+        args: &[Node<N>],
+        valores: Vec<Value<N>>,
+        span: &TextSpan,
+        escopo: &Escopo<'a, N>,
+    ) -> Result<Invocacao<'a, N>, EvalError> {
+        let (ambiente, profundidade) = (escopo.ambiente, escopo.profundidade);
+        let Some(funcao) = ambiente.funcao(nome) else {
+            let numeros = valores
+                .iter()
+                .zip(args)
+                .map(|(valor, arg)| valor.como_numero(arg.span()).cloned())
+                .collect::<Result<Vec<_>, _>>()?;
+            let resultado = match ambiente.registro().chamar(nome, &numeros) {
+                Some(resultado) => resultado,
+                None => funcoes::aplicar(nome, &numeros),
+            };
+            return resultado.map(|numero| Invocacao::Valor(Value::Numero(numero))).map_err(|erro| erro.com_span(span));
+        };
+        if profundidade >= self.limite_de_recursao {
+            return Err(EvalError::RecursaoExcessiva {
+                nome: nome.to_string(),
+                limite: self.limite_de_recursao,
+                span: span.clone(),
+            });
+        }
+        let escopo = Escopo {
+            ambiente,
+            parametros: funcao.parametros().iter().map(String::as_str).zip(valores).collect(),
+            profundidade: profundidade + 1,
+        };
+        Ok(Invocacao::Corpo(funcao.corpo(), escopo))
+    }
+
+    /// Executa as instruções do programa em ordem, gravando as atribuições e
+    /// as definições de funções no ambiente, e devolve o valor da última
+    /// instrução que não é uma definição; um programa sem elas vale 0.
+    pub fn executar<N: Number>(&self, ast: &Ast<N>, ambiente: &mut Environment<N>) -> Result<Value<N>, EvalError> {
+        let mut ultimo = Value::Numero(N::de_inteiro(0));
+        for instrucao in ast.instrucoes() {
+            match instrucao {
+                Statement::Expressao(expressao) => ultimo = self.avaliar_em(expressao, ambiente)?,
+                Statement::Atribuicao { nome, valor, .. } => {
+                    ultimo = self.avaliar_em(valor, ambiente)?;
+                    ambiente.definir(nome.clone(), ultimo.clone());
+                }
+                Statement::Definicao { nome, parametros, corpo, .. } => {
+                    ambiente.definir_funcao(nome.clone(), UserFunction::new(parametros.clone(), corpo.clone()));
+                }
+            }
+        }
+        Ok(ultimo)
+    }
+
+    // Aplica a operação de um `Node::BinaryOp` aos valores dos dois lados; os
+    // erros de tipo apontam para o lado de tipo errado
+    pub(crate) fn aplicar_binario<N: Number>(
+        &self,
+        no: &Node<N>,
+        esquerda: &Value<N>,
+        direita: &Value<N>,
+    ) -> Result<Value<N>, EvalError> {
+        let Node::BinaryOp { op, left, right, span } = no else {
+            unreachable!("apenas operações binárias têm dois operandos")
+        };
+        match op {
+            TokenKind::IgualIgual | TokenKind::Diferente => {
+                let iguais = match (esquerda, direita) {
+                    (Value::Numero(esquerda), Value::Numero(direita)) => esquerda == direita,
+                    (Value::Booleano(esquerda), Value::Booleano(direita)) => esquerda == direita,
+                    _ => return Err(direita.erro_de_tipo(esquerda.tipo(), right.span())),
+                };
+                Ok(Value::Booleano(iguais == (*op == TokenKind::IgualIgual)))
+            }
+            TokenKind::E | TokenKind::Ou => {
+                let esquerda = esquerda.como_booleano(left.span())?;
+                let direita = direita.como_booleano(right.span())?;
+                Ok(Value::Booleano(if *op == TokenKind::E {
+                    esquerda && direita
+                } else {
+                    esquerda || direita
+                }))
+            }
+            _ => {
+                let esquerda = esquerda.como_numero(left.span())?;
+                let direita = direita.como_numero(right.span())?;
+                self.aplicar_aritmetico(op, esquerda, direita, span)
+            }
+        }
+    }
+
+    fn aplicar_aritmetico<N: Number>(&self, op: &TokenKind, esquerda: &N, direita: &N, span: &TextSpan) -> Result<Value<N>, EvalError> {
+        let ordem = || esquerda.partial_cmp(direita).ok_or(ErroAritmetico::Indefinido);
+        match op {
+            TokenKind::Mais => esquerda.somar(direita).map(Value::Numero),
+            TokenKind::Menos => esquerda.subtrair(direita).map(Value::Numero),
+            TokenKind::Asterisco => esquerda.multiplicar(direita).map(Value::Numero),
+            TokenKind::Barra => esquerda.dividir(direita, self.arredondamento).map(Value::Numero),
+            TokenKind::Porcento => esquerda.resto(direita, self.arredondamento).map(Value::Numero),
+            TokenKind::BarraDupla => esquerda.quociente(direita, Rounding::Piso).map(Value::Numero),
+            TokenKind::Mod => esquerda.resto(direita, Rounding::Euclides).map(Value::Numero),
+            TokenKind::Potencia => esquerda.potencia(direita).map(Value::Numero),
+            TokenKind::Menor => ordem().map(|ordem| Value::Booleano(ordem.is_lt())),
+            TokenKind::MenorOuIgual => ordem().map(|ordem| Value::Booleano(ordem.is_le())),
+            TokenKind::Maior => ordem().map(|ordem| Value::Booleano(ordem.is_gt())),
+            TokenKind::MaiorOuIgual => ordem().map(|ordem| Value::Booleano(ordem.is_ge())),
+            _ => {
+                return Err(EvalError::OperadorNaoSuportado {
+                    op: op.clone(),
+                    span: span.clone(),
+                })
+            }
+        }
+        .map_err(|erro| erro.com_span(span))
+    }
+}
+
+impl<N: Number> Node<N> {
+    /// Avalia o nó com a configuração padrão.
+    pub fn avaliar(&self) -> Result<Value<N>, EvalError> {
+        Evaluator::default().avaliar(self)
+    }
+
+    /// Avalia o nó com a configuração padrão, buscando as variáveis no ambiente.
+    pub fn avaliar_em(&self, ambiente: &Environment<N>) -> Result<Value<N>, EvalError> {
+        Evaluator::default().avaliar_em(self, ambiente)
+    }
+}
+
+impl<N: Number> Ast<N> {
+    /// Avalia o programa inteiro com a configuração padrão; um programa vazio vale 0.
+    pub fn avaliar(&self) -> Result<Value<N>, EvalError> {
+        self.avaliar_em(&Environment::new())
+    }
+
+    /// Avalia o programa com a configuração padrão a partir de uma cópia do
+    /// ambiente, que não é alterado pelas atribuições.
+    pub fn avaliar_em(&self, ambiente: &Environment<N>) -> Result<Value<N>, EvalError> {
+        self.executar(&mut ambiente.clone())
+    }
+
+    /// Executa o programa com a configuração padrão, gravando as atribuições no ambiente.
+    pub fn executar(&self, ambiente: &mut Environment<N>) -> Result<Value<N>, EvalError> {
+        Evaluator::default().executar(self, ambiente)
+    }
+}
+
+pub(crate) fn variavel<N: Number>(nome: &str, span: &TextSpan, ambiente: &Environment<N>) -> Result<Value<N>, EvalError> {
+    ambiente.obter(nome).cloned().ok_or_else(|| EvalError::VariavelIndefinida {
+        nome: nome.to_string(),
+        span: span.clone(),
+    })
+}
+
+pub(crate) fn aplicar_unario<N: Number>(op: &TokenKind, valor: &Value<N>, span: &TextSpan) -> Result<Value<N>, EvalError> {
+    match op {
+        TokenKind::Nao => Ok(Value::Booleano(!valor.como_booleano(span)?)),
+        TokenKind::Mais => Ok(Value::Numero(valor.como_numero(span)?.clone())),
+        TokenKind::Menos => valor.como_numero(span)?.negar().map(Value::Numero).map_err(|erro| erro.com_span(span)),
+        _ => Err(EvalError::OperadorNaoSuportado {
+            op: op.clone(),
+            span: span.clone(),
+        }),
+    }
+}
+
+// O resultado de `&&` ou `||` quando o lado esquerdo já o decide, como em
+// `falso && x`; os demais operadores sempre precisam do lado direito
+pub(crate) fn curto_circuito<N: Number>(
+    op: &TokenKind,
+    esquerda: &Value<N>,
+    span: &TextSpan,
+) -> Result<Option<Value<N>>, EvalError> {
+    let decide = match op {
+        TokenKind::E => false,
+        TokenKind::Ou => true,
+        _ => return Ok(None),
+    };
+    let esquerda = esquerda.como_booleano(span)?;
+    Ok((esquerda == decide).then_some(Value::Booleano(decide)))
+}
+
+// Um passo pendente da avaliação de um nó, na pilha de tarefas
+enum Tarefa<'a, N> {
+    // Avalia o nó e empilha o seu valor
+    Avaliar(&'a Node<N>),
+    // Decide, com o lado esquerdo já avaliado, se o lado direito é preciso
+    Direita(&'a Node<N>),
+    // Combina os dois lados já avaliados
+    Binaria(&'a Node<N>),
+    Unaria(&'a Node<N>),
+    // Chama a função com os argumentos já avaliados
+    Chamar(&'a Node<N>),
+    // Avalia o ramo escolhido pela condição já avaliada
+    Ramo(&'a Node<N>),
+    // O corpo de uma função definida terminou; volta ao escopo de quem a chamou
+    Retornar,
+}
+
+// O que resta de uma chamada depois de começada: o resultado de uma função
+// embutida ou registrada, ou o corpo de uma função definida com o seu escopo
+enum Invocacao<'a, N> {
+    Valor(Value<N>),
+    Corpo(&'a Node<N>, Escopo<'a, N>),
+}
+
+// Variáveis visíveis durante a avaliação: os parâmetros da função em
+// andamento, se houver, e as variáveis do ambiente. Uma chamada começa um
+// escopo novo, de modo que a função não enxerga os parâmetros de quem a chamou
+pub(crate) struct Escopo<'a, N> {
+    ambiente: &'a Environment<N>,
+    parametros: Vec<(&'a str, Value<N>)>,
+    profundidade: usize,
+}
+
+impl<'a, N: Number> Escopo<'a, N> {
+    pub(crate) fn global(ambiente: &'a Environment<N>) -> Self {
+        Self {
+            ambiente,
+            parametros: Vec::new(),
+            profundidade: 0,
+        }
+    }
+
+    fn variavel(&self, nome: &str, span: &TextSpan) -> Result<Value<N>, EvalError> {
+        match self.parametros.iter().find(|(parametro, _)| *parametro == nome) {
+            Some((_, valor)) => Ok(valor.clone()),
+            None => variavel(nome, span, self.ambiente),
+        }
+    }
+}
+
+// Confere o nome e a quantidade de argumentos antes de avaliá-los
+pub(crate) fn verificar_chamada<N: Number>(
+    nome: &str,
+    args: &[Node<N>],
+    span: &TextSpan,
+    ambiente: &Environment<N>,
+) -> Result<(), EvalError> {
+    let esperada = ambiente
+        .funcao(nome)
+        .map(|funcao| Arity::Exata(funcao.parametros().len()))
+        .or_else(|| ambiente.registro().aridade(nome))
+        .or_else(|| funcoes::aridade(nome))
+        .ok_or_else(|| EvalError::FuncaoIndefinida {
+            nome: nome.to_string(),
+            span: span.clone(),
+        })?;
+    if esperada.aceita(args.len()) {
+        return Ok(());
+    }
+    let span = match esperada.maxima() {
+        Some(maxima) if args.len() > maxima => {
+            TextSpan::combinar(args[maxima..].iter().map(|arg| arg.span().clone()).collect())
+        }
+        _ => span.clone(),
+    };
+    Err(EvalError::AridadeIncorreta {
+        nome: nome.to_string(),
+        esperada,
+        recebida: args.len(),
+        span,
+    })
+}
@@ -0,0 +1,205 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+use crate::ast::avaliador::Rounding;
+use crate::numero::{ErroAritmetico, Number, Resultado};
+
+/// Quantos argumentos uma função aceita.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Exata(usize),
+    /// A função aceita qualquer quantidade a partir desta, como `max`.
+    AoMenos(usize),
+}
+
+impl Arity {
+    pub fn aceita(&self, quantidade: usize) -> bool {
+        match self {
+            Arity::Exata(esperada) => quantidade == *esperada,
+            Arity::AoMenos(minima) => quantidade >= *minima,
+        }
+    }
+
+    /// A maior quantidade de argumentos aceita, se houver limite.
+    pub fn maxima(&self) -> Option<usize> {
+        match self {
+            Arity::Exata(esperada) => Some(*esperada),
+            Arity::AoMenos(_) => None,
+        }
+    }
+}
+
+impl Display for Arity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (prefixo, quantidade) = match self {
+            Arity::Exata(quantidade) => ("", *quantidade),
+            Arity::AoMenos(quantidade) => ("pelo menos ", *quantidade),
+        };
+        let plural = if quantidade == 1 { "" } else { "s" };
+        write!(f, "{}{} argumento{}", prefixo, quantidade, plural)
+    }
+}
+
+type Implementacao<N> = Arc<dyn Fn(&[N]) -> Resultado<N> + Send + Sync>;
+
+/// Funções implementadas em Rust pelo programa que usa a biblioteca.
+///
+/// O registro acompanha o [`Environment`](crate::ast::ambiente::Environment)
+/// e é consultado pelo avaliador a cada chamada: uma função definida no
+/// próprio programa tem prioridade sobre uma registrada, que por sua vez tem
+/// prioridade sobre uma embutida de mesmo nome. A quantidade de argumentos é
+/// verificada antes da chamada, de modo que a função recebe sempre uma
+/// quantidade aceita pela sua [`Arity`].
+///
+/// ```
+/// use operations::ast::ambiente::Environment;
+/// use operations::ast::funcoes::{Arity, FunctionRegistry};
+/// use operations::ast::parser::Parser;
+/// use operations::tokenize;
+///
+/// let registro = FunctionRegistry::new().com("taxa", Arity::Exata(1), |args: &[i64]| Ok(args[0] * 5 / 100));
+/// let ambiente = Environment::new().com_registro(registro);
+/// let ast = Parser::new(tokenize("taxa(300) + 1").unwrap()).parse().unwrap();
+/// assert_eq!(ast.avaliar_em(&ambiente).unwrap(), 16);
+/// ```
+#[derive(Clone)]
+pub struct FunctionRegistry<N = i64> {
+    funcoes: HashMap<String, (Arity, Implementacao<N>)>,
+}
+
+impl<N> Default for FunctionRegistry<N> {
+    fn default() -> Self {
+        Self {
+            funcoes: HashMap::new(),
+        }
+    }
+}
+
+impl<N> FunctionRegistry<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra a função e devolve o registro, para montá-lo em uma única expressão.
+    pub fn com<F>(mut self, nome: impl Into<String>, aridade: Arity, funcao: F) -> Self
+    where
+        F: Fn(&[N]) -> Resultado<N> + Send + Sync + 'static,
+    {
+        self.registrar(nome, aridade, funcao);
+        self
+    }
+
+    /// Registra a função, substituindo outra de mesmo nome; devolve `true`
+    /// se ela já existia.
+    pub fn registrar<F>(&mut self, nome: impl Into<String>, aridade: Arity, funcao: F) -> bool
+    where
+        F: Fn(&[N]) -> Resultado<N> + Send + Sync + 'static,
+    {
+        self.funcoes.insert(nome.into(), (aridade, Arc::new(funcao))).is_some()
+    }
+
+    /// Remove a função; devolve `true` se ela existia.
+    pub fn remover(&mut self, nome: &str) -> bool {
+        self.funcoes.remove(nome).is_some()
+    }
+
+    pub fn aridade(&self, nome: &str) -> Option<Arity> {
+        self.funcoes.get(nome).map(|(aridade, _)| *aridade)
+    }
+
+    /// Os nomes registrados, em ordem alfabética.
+    pub fn nomes(&self) -> Vec<&str> {
+        let mut nomes: Vec<&str> = self.funcoes.keys().map(String::as_str).collect();
+        nomes.sort_unstable();
+        nomes
+    }
+
+    // Chama a função registrada, ou devolve `None` se o nome não estiver registrado
+    pub(crate) fn chamar(&self, nome: &str, argumentos: &[N]) -> Option<Resultado<N>> {
+        self.funcoes.get(nome).map(|(_, funcao)| funcao(argumentos))
+    }
+}
+
+impl<N> Debug for FunctionRegistry<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.nomes().into_iter().map(|nome| (nome, self.funcoes[nome].0)))
+            .finish()
+    }
+}
+
+/// Nomes das funções embutidas, na ordem em que aparecem na documentação.
+pub const EMBUTIDAS: [&str; 7] = ["abs", "min", "max", "gcd", "lcm", "sqrt", "pow"];
+
+/// Aridade da função embutida com este nome, ou `None` se não houver uma.
+///
+/// - `abs(x)` e `sqrt(x)`: valor absoluto e raiz quadrada, que nos domínios
+///   inteiros é arredondada para baixo e no racional só existe quando exata;
+/// - `min(x, ...)` e `max(x, ...)`: o menor e o maior dos argumentos;
+/// - `gcd(a, b)` e `lcm(a, b)`: máximo divisor comum e mínimo múltiplo
+///   comum, sempre não negativos;
+/// - `pow(a, b)`: o mesmo que `a ^ b`.
+pub fn aridade(nome: &str) -> Option<Arity> {
+    match nome {
+        "abs" | "sqrt" => Some(Arity::Exata(1)),
+        "min" | "max" => Some(Arity::AoMenos(1)),
+        "gcd" | "lcm" | "pow" => Some(Arity::Exata(2)),
+        _ => None,
+    }
+}
+
+// Aplica a função a argumentos cuja quantidade já foi verificada
+pub(crate) fn aplicar<N: Number>(nome: &str, argumentos: &[N]) -> Resultado<N> {
+    match (nome, argumentos) {
+        ("abs", [x]) => abs(x),
+        ("sqrt", [x]) => x.raiz_quadrada(),
+        ("min", [primeiro, resto @ ..]) => extremo(primeiro, resto, Ordering::Less),
+        ("max", [primeiro, resto @ ..]) => extremo(primeiro, resto, Ordering::Greater),
+        ("gcd", [a, b]) => mdc(a, b),
+        ("lcm", [a, b]) => mmc(a, b),
+        ("pow", [base, expoente]) => base.potencia(expoente),
+        _ => unreachable!("a aridade de '{}' é verificada antes da chamada", nome),
+    }
+}
+
+fn abs<N: Number>(x: &N) -> Resultado<N> {
+    match x.partial_cmp(&N::de_inteiro(0)) {
+        Some(Ordering::Less) => x.negar(),
+        Some(_) => Ok(x.clone()),
+        None => Err(ErroAritmetico::Indefinido),
+    }
+}
+
+fn extremo<N: Number>(primeiro: &N, resto: &[N], procurado: Ordering) -> Resultado<N> {
+    let mut escolhido = primeiro;
+    for valor in resto {
+        match valor.partial_cmp(escolhido) {
+            Some(ordem) if ordem == procurado => escolhido = valor,
+            Some(_) => {}
+            None => return Err(ErroAritmetico::Indefinido),
+        }
+    }
+    Ok(escolhido.clone())
+}
+
+// Algoritmo de Euclides; nos domínios real e racional o resultado é o maior
+// valor do qual os dois argumentos são múltiplos inteiros
+fn mdc<N: Number>(a: &N, b: &N) -> Resultado<N> {
+    let zero = N::de_inteiro(0);
+    let (mut a, mut b) = (abs(a)?, abs(b)?);
+    while b != zero {
+        let resto = a.resto(&b, Rounding::Truncar)?;
+        a = b;
+        b = resto;
+    }
+    Ok(a)
+}
+
+fn mmc<N: Number>(a: &N, b: &N) -> Resultado<N> {
+    let divisor = mdc(a, b)?;
+    if divisor == N::de_inteiro(0) {
+        return Ok(divisor);
+    }
+    abs(&a.dividir(&divisor, Rounding::Truncar)?.multiplicar(b)?)
+}
@@ -1,10 +1,15 @@
+use std::error::Error;
 use std::fmt::Display;
 use std::fmt::Formatter;
+use crate::numero::inteiro::BigInt;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TextSpan {
     pub start: usize,
     pub end: usize,
+    /// O texto do trecho, guardado só nos spans de tokens. Os spans de nós
+    /// compostos, e portanto os da maioria dos erros de avaliação, têm o
+    /// literal vazio; use [`TextSpan::literal`] com a entrada para obter o texto.
     pub literal: String,
 }
 
@@ -17,20 +22,19 @@ impl TextSpan {
         }
     }
 
-    pub fn combinar(mut spans: Vec<TextSpan>) -> TextSpan {
-        if spans.is_empty() {
-            println!("Não é possível combinar spans vazios");
-        }
-        spans.sort_by(|a, b| a.start.cmp(&b.start));
-
-        let start = spans.first().unwrap().start;
-        let end = spans.last().unwrap().end;
+    /// Trecho que vai do início do primeiro span ao fim do último.
+    ///
+    /// O resultado guarda só as posições, com o literal vazio: copiar o
+    /// texto em cada nó composto tornaria a árvore quadrática no tamanho da
+    /// entrada. Use [`TextSpan::literal`] para obter o texto do trecho.
+    ///
+    /// `spans` não pode ser vazio; uma lista vazia resulta no trecho `0..0`.
+    pub fn combinar(spans: Vec<TextSpan>) -> TextSpan {
+        debug_assert!(!spans.is_empty(), "não é possível combinar uma lista vazia de spans");
+        let start = spans.iter().map(|span| span.start).min().unwrap_or(0);
+        let end = spans.iter().map(|span| span.end).max().unwrap_or(start);
 
-        TextSpan::new(
-            start,
-            end,
-            spans.into_iter().map(|span| span.literal).collect(),
-        )
+        TextSpan::new(start, end, String::new())
     }
 
     pub fn comprimento(&self) -> usize {
@@ -45,10 +49,44 @@ impl TextSpan {
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenKind {
     Numero(i64),
+    /// Literal inteiro que não cabe em `i64`.
+    NumeroGrande(BigInt),
+    Decimal(f64),
+    /// As palavras reservadas `verdadeiro` e `falso`.
+    Booleano(bool),
+    /// Nome de uma variável, como `preco` ou `taxa_2`.
+    Identificador(String),
     Mais,
     Menos,
     Asterisco,
     Barra,
+    BarraDupla,
+    Porcento,
+    Mod,
+    Potencia,
+    IgualIgual,
+    Diferente,
+    Menor,
+    MenorOuIgual,
+    Maior,
+    MaiorOuIgual,
+    E,
+    Ou,
+    Nao,
+    /// As palavras reservadas `se`, `entao` e `senao` de uma condicional;
+    /// `então` e `senão` também são aceitas.
+    Se,
+    Entao,
+    Senao,
+    /// O `?` e o `:` da forma curta da condicional, `c ? a : b`.
+    Interrogacao,
+    DoisPontos,
+    Igual,
+    PontoEVirgula,
+    /// Separa os argumentos de uma chamada de função.
+    Virgula,
+    /// Quebra de linha, que separa instruções como `;`.
+    NovaLinha,
     FimDeArquivo,
     Erro,
     EspacoEmBranco,
@@ -59,11 +97,36 @@ pub enum TokenKind {
 impl Display for TokenKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            TokenKind::Numero(_) => write!(f, "Número"),
+            TokenKind::Numero(_) | TokenKind::NumeroGrande(_) => write!(f, "Número"),
+            TokenKind::Decimal(_) => write!(f, "Número decimal"),
+            TokenKind::Booleano(_) => write!(f, "Booleano"),
+            TokenKind::Identificador(_) => write!(f, "Identificador"),
             TokenKind::Mais => write!(f, "+"),
             TokenKind::Menos => write!(f, "-"),
             TokenKind::Asterisco => write!(f, "*"),
             TokenKind::Barra => write!(f, "/"),
+            TokenKind::BarraDupla => write!(f, "//"),
+            TokenKind::Porcento => write!(f, "%"),
+            TokenKind::Mod => write!(f, "mod"),
+            TokenKind::Potencia => write!(f, "^"),
+            TokenKind::IgualIgual => write!(f, "=="),
+            TokenKind::Diferente => write!(f, "!="),
+            TokenKind::Menor => write!(f, "<"),
+            TokenKind::MenorOuIgual => write!(f, "<="),
+            TokenKind::Maior => write!(f, ">"),
+            TokenKind::MaiorOuIgual => write!(f, ">="),
+            TokenKind::E => write!(f, "&&"),
+            TokenKind::Ou => write!(f, "||"),
+            TokenKind::Nao => write!(f, "!"),
+            TokenKind::Se => write!(f, "se"),
+            TokenKind::Entao => write!(f, "entao"),
+            TokenKind::Senao => write!(f, "senao"),
+            TokenKind::Interrogacao => write!(f, "?"),
+            TokenKind::DoisPontos => write!(f, ":"),
+            TokenKind::Igual => write!(f, "="),
+            TokenKind::PontoEVirgula => write!(f, ";"),
+            TokenKind::Virgula => write!(f, ","),
+            TokenKind::NovaLinha => write!(f, "Nova Linha"),
             TokenKind::FimDeArquivo => write!(f, "Fim de Arquivo"),
             TokenKind::Erro => write!(f, "Erro"),
             TokenKind::EspacoEmBranco => write!(f, "Espaço em Branco"),
@@ -85,9 +148,20 @@ impl Token {
     }
 }
 
+/// Divide a entrada em tokens em uma única passagem.
+///
+/// `current_pos` é um deslocamento em bytes e sempre cai em uma fronteira de
+/// caractere, de modo que os spans produzidos podem fatiar a entrada mesmo
+/// quando ela contém caracteres fora do ASCII.
+///
+/// Como iterador, produz todos os tokens (inclusive espaços em branco),
+/// termina com um único [`TokenKind::FimDeArquivo`] posicionado no fim da
+/// entrada e depois disso devolve apenas `None`. Quebras de linha não são
+/// espaço em branco: viram [`TokenKind::NovaLinha`], que separa instruções.
 pub struct Lexer<'a> {
     input: &'a str,
     current_pos: usize,
+    terminou: bool,
 }
 
 impl<'a> Lexer<'a> {
@@ -95,31 +169,26 @@ impl<'a> Lexer<'a> {
         Self {
             input,
             current_pos: 0,
+            terminou: false,
         }
     }
 
     pub fn proximo_token(&mut self) -> Option<Token> {
-        if self.current_pos == self.input.len() {
-            self.current_pos += 1;
-            return Some(Token::new(
-                TokenKind::FimDeArquivo,
-                TextSpan::new(0, 0, '\0'.to_string()),
-            ));
+        if self.terminou {
+            return None;
         }
-    
+
         let start = self.current_pos;
         let c = self.caractere_atual();
     
         if let Some(c) = c {
-            let kind = if c == '-' && self.caractere_seguinte().map_or(false, |next_c| next_c.is_digit(10)) {
-                // Lidar com números negativos
-                self.consumir(); // Consumir o sinal de menos
-                let number = self.consumir_numero();
-                TokenKind::Numero(-number) // Negar o número
-            } else if Self::eh_numero(&c) {
-                // Número regular
-                let number: i64 = self.consumir_numero();
-                TokenKind::Numero(number)
+            let kind = if Self::eh_numero(&c) || (c == '.' && self.seguinte_eh_digito()) {
+                self.consumir_numero()
+            } else if Self::eh_letra(&c) {
+                self.consumir_palavra()
+            } else if c == '\n' {
+                self.consumir();
+                TokenKind::NovaLinha
             } else if Self::eh_espaco_em_branco(&c) {
                 self.consumir();
                 TokenKind::EspacoEmBranco
@@ -133,21 +202,45 @@ impl<'a> Lexer<'a> {
     
             Some(Token::new(kind, span))
         } else {
-            None
+            self.terminou = true;
+            Some(Token::new(
+                TokenKind::FimDeArquivo,
+                TextSpan::new(start, start, String::new()),
+            ))
         }
     }
     
-    fn caractere_seguinte(&self) -> Option<char> {
-        self.input.chars().nth(self.current_pos + 1)
-    }
-
     fn consumir_pontuacao(&mut self, c: char) -> TokenKind {
         self.consumir();
         match c {
             '+' => TokenKind::Mais,
-            '-' => TokenKind::Menos,
-            '*' => TokenKind::Asterisco,
-            '/' => TokenKind::Barra,
+            '-' | '−' => TokenKind::Menos,
+            '*' if self.caractere_atual() == Some('*') => {
+                self.consumir();
+                TokenKind::Potencia
+            }
+            '^' => TokenKind::Potencia,
+            '*' | '×' => TokenKind::Asterisco,
+            '/' if self.caractere_atual() == Some('/') => {
+                self.consumir();
+                TokenKind::BarraDupla
+            }
+            '/' | '÷' => TokenKind::Barra,
+            '%' => TokenKind::Porcento,
+            '=' if self.consumir_se('=') => TokenKind::IgualIgual,
+            '=' => TokenKind::Igual,
+            '!' if self.consumir_se('=') => TokenKind::Diferente,
+            '!' => TokenKind::Nao,
+            '<' if self.consumir_se('=') => TokenKind::MenorOuIgual,
+            '<' => TokenKind::Menor,
+            '>' if self.consumir_se('=') => TokenKind::MaiorOuIgual,
+            '>' => TokenKind::Maior,
+            '&' if self.consumir_se('&') => TokenKind::E,
+            '|' if self.consumir_se('|') => TokenKind::Ou,
+            ';' => TokenKind::PontoEVirgula,
+            ',' => TokenKind::Virgula,
+            '?' => TokenKind::Interrogacao,
+            ':' => TokenKind::DoisPontos,
             '(' => TokenKind::ParentesesEsquerdo,
             ')' => TokenKind::ParentesesDireito,
             _ => TokenKind::Erro,
@@ -155,7 +248,44 @@ impl<'a> Lexer<'a> {
     }
 
     fn eh_numero(c: &char) -> bool {
-        c.is_digit(10)
+        c.is_ascii_digit()
+    }
+
+    fn eh_letra(c: &char) -> bool {
+        c.is_alphabetic() || *c == '_'
+    }
+
+    // Consome o próximo caractere se ele for o esperado, completando
+    // operadores de dois caracteres como `<=`
+    fn consumir_se(&mut self, esperado: char) -> bool {
+        if self.caractere_atual() == Some(esperado) {
+            self.consumir();
+            true
+        } else {
+            false
+        }
+    }
+
+    // Palavras inteiras são consumidas de uma vez; fora as palavras
+    // reservadas `mod`, `verdadeiro`, `falso` e as da condicional, toda palavra é um identificador
+    fn consumir_palavra(&mut self) -> TokenKind {
+        let inicio = self.current_pos;
+        while let Some(c) = self.caractere_atual() {
+            if Self::eh_letra(&c) || c.is_ascii_digit() {
+                self.consumir();
+            } else {
+                break;
+            }
+        }
+        match &self.input[inicio..self.current_pos] {
+            "mod" => TokenKind::Mod,
+            "verdadeiro" => TokenKind::Booleano(true),
+            "falso" => TokenKind::Booleano(false),
+            "se" => TokenKind::Se,
+            "entao" | "então" => TokenKind::Entao,
+            "senao" | "senão" => TokenKind::Senao,
+            palavra => TokenKind::Identificador(palavra.to_string()),
+        }
     }
 
     fn eh_espaco_em_branco(c: &char) -> bool {
@@ -163,28 +293,116 @@ impl<'a> Lexer<'a> {
     }
 
     fn caractere_atual(&self) -> Option<char> {
-        self.input.chars().nth(self.current_pos)
+        self.input.get(self.current_pos..)?.chars().next()
     }
 
     fn consumir(&mut self) -> Option<char> {
-        if self.current_pos >= self.input.len() {
-            return None;
+        let c = self.caractere_atual()?;
+        self.current_pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn seguinte_eh_digito(&self) -> bool {
+        self.input[self.current_pos..]
+            .chars()
+            .nth(1)
+            .is_some_and(|c| c.is_ascii_digit())
+    }
+
+    // Um expoente só começa em 'e' se for seguido de dígitos, com sinal opcional
+    fn expoente_a_seguir(&self) -> bool {
+        let mut resto = self.input[self.current_pos..].chars();
+        if !matches!(resto.next(), Some('e' | 'E')) {
+            return false;
         }
-        let c = self.caractere_atual();
-        self.current_pos += 1;
-        c
+        match resto.next() {
+            Some('+' | '-') => resto.next(),
+            c => c,
+        }
+        .is_some_and(|c| c.is_ascii_digit())
     }
 
-    fn consumir_numero(&mut self) -> i64 {
-        let mut number: i64 = 0;
-        while let Some(c) = self.caractere_atual() {
-            if c.is_digit(10) {
-                self.consumir().unwrap();
-                number = number * 10 + c.to_digit(10).unwrap() as i64;
-            } else {
-                break;
+    fn consumir_digitos(&mut self) {
+        while self.caractere_atual().is_some_and(|c| c.is_ascii_digit()) {
+            self.consumir();
+        }
+    }
+
+    // Lê um inteiro, como `42`, ou um decimal com parte fracionária e/ou
+    // expoente, como `3.14`, `.5` e `1e-3`
+    fn consumir_numero(&mut self) -> TokenKind {
+        let inicio = self.current_pos;
+        let mut decimal = false;
+
+        self.consumir_digitos();
+        if self.caractere_atual() == Some('.') && self.seguinte_eh_digito() {
+            self.consumir();
+            self.consumir_digitos();
+            decimal = true;
+        }
+        if self.expoente_a_seguir() {
+            self.consumir();
+            if matches!(self.caractere_atual(), Some('+' | '-')) {
+                self.consumir();
             }
+            self.consumir_digitos();
+            decimal = true;
+        }
+
+        let texto = &self.input[inicio..self.current_pos];
+        if decimal {
+            TokenKind::Decimal(texto.parse().unwrap())
+        } else {
+            match texto.parse() {
+                Ok(valor) => TokenKind::Numero(valor),
+                // Literais além do limite de i64 ficam para o domínio da avaliação decidir
+                Err(_) => TokenKind::NumeroGrande(BigInt::de_digitos(texto).unwrap()),
+            }
+        }
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        self.proximo_token()
+    }
+}
+
+/// Erro de análise léxica: um caractere que não inicia nenhum token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexError {
+    pub token: Token,
+}
+
+impl LexError {
+    pub fn span(&self) -> &TextSpan {
+        &self.token.span
+    }
+}
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "caractere inválido '{}'", self.token.span.literal)
+    }
+}
+
+impl Error for LexError {}
+
+/// Divide a entrada em tokens prontos para o [`Parser`](crate::ast::parser::Parser).
+///
+/// Os espaços em branco são descartados, as quebras de linha são mantidas e
+/// o último token é sempre o [`TokenKind::FimDeArquivo`]. O primeiro
+/// caractere inválido interrompe a análise com um [`LexError`].
+pub fn tokenize(input: &str) -> Result<Vec<Token>, LexError> {
+    let mut tokens = Vec::new();
+    for token in Lexer::new(input) {
+        match token.kind {
+            TokenKind::EspacoEmBranco => {}
+            TokenKind::Erro => return Err(LexError { token }),
+            _ => tokens.push(token),
         }
-        number
     }
+    Ok(tokens)
 }
@@ -1,2 +1,8 @@
+pub mod ambiente;
+pub mod avaliador;
+pub mod funcoes;
 pub mod lexer;
-pub mod parser;
\ No newline at end of file
+pub mod operadores;
+pub mod parser;
+pub mod rastro;
+pub mod valor;
@@ -0,0 +1,161 @@
+use crate::ast::lexer::TokenKind;
+
+/// Como uma sequência de operadores de mesma precedência é agrupada.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` vale `(a - b) - c`.
+    Esquerda,
+    /// `a ^ b ^ c` vale `a ^ (b ^ c)`.
+    Direita,
+}
+
+/// Quantos operandos um operador recebe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatorArity {
+    /// Escrito antes do único operando, como `-x` ou `!x`.
+    Unario,
+    /// Escrito entre os dois operandos, como `a + b`.
+    Binario,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Entrada {
+    simbolo: TokenKind,
+    aridade: OperatorArity,
+    precedencia: u8,
+    associatividade: Associativity,
+}
+
+/// Precedência, associatividade e aridade dos operadores reconhecidos pelo
+/// [`Parser`](crate::ast::parser::Parser).
+///
+/// Quanto maior a precedência, mais forte o operador liga. Um operador
+/// unário liga o seu operando aos operadores binários seguintes de
+/// precedência maior; com [`Associativity::Direita`], também aos de mesma
+/// precedência. Um símbolo pode ser ao mesmo tempo unário e binário, como o
+/// `-`. Os símbolos são os do [`Lexer`](crate::ast::lexer::Lexer), e um
+/// símbolo fora da tabela não é aceito como operador; o significado de cada
+/// operador não muda, e um operador sem significado para o avaliador, como
+/// `!` em posição binária, resulta em
+/// [`EvalError::OperadorNaoSuportado`](crate::ast::avaliador::EvalError::OperadorNaoSuportado).
+///
+/// A tabela padrão, de [`OperatorTable::new`], é, do mais fraco ao mais
+/// forte: a condicional `?` (0, à direita), `||` (1), `&&` (2), `==` e `!=`
+/// (3), `<`, `<=`, `>` e `>=` (4), `+` e `-` (5), `*`, `/`, `//`, `%` e `mod`
+/// (6), os sinais unários `+`, `-` e `!` (7) e `^` (8, à direita). Os demais
+/// binários associam à esquerda.
+///
+/// ```
+/// use operations::ast::lexer::TokenKind;
+/// use operations::ast::operadores::{Associativity, OperatorArity, OperatorTable};
+/// use operations::ast::parser::Parser;
+/// use operations::tokenize;
+///
+/// // O menos unário passa a ligar mais forte que a potência
+/// let tabela = OperatorTable::new().com(TokenKind::Menos, OperatorArity::Unario, 9, Associativity::Esquerda);
+/// let ast = Parser::new(tokenize("-2 ^ 2").unwrap()).com_operadores(tabela).parse().unwrap();
+/// assert_eq!(ast.avaliar().unwrap(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorTable {
+    entradas: Vec<Entrada>,
+}
+
+impl Default for OperatorTable {
+    fn default() -> Self {
+        use Associativity::{Direita, Esquerda};
+        use OperatorArity::{Binario, Unario};
+        let padrao = [
+            (TokenKind::Interrogacao, Binario, 0, Direita),
+            (TokenKind::Ou, Binario, 1, Esquerda),
+            (TokenKind::E, Binario, 2, Esquerda),
+            (TokenKind::IgualIgual, Binario, 3, Esquerda),
+            (TokenKind::Diferente, Binario, 3, Esquerda),
+            (TokenKind::Menor, Binario, 4, Esquerda),
+            (TokenKind::MenorOuIgual, Binario, 4, Esquerda),
+            (TokenKind::Maior, Binario, 4, Esquerda),
+            (TokenKind::MaiorOuIgual, Binario, 4, Esquerda),
+            (TokenKind::Mais, Binario, 5, Esquerda),
+            (TokenKind::Menos, Binario, 5, Esquerda),
+            (TokenKind::Asterisco, Binario, 6, Esquerda),
+            (TokenKind::Barra, Binario, 6, Esquerda),
+            (TokenKind::BarraDupla, Binario, 6, Esquerda),
+            (TokenKind::Porcento, Binario, 6, Esquerda),
+            (TokenKind::Mod, Binario, 6, Esquerda),
+            (TokenKind::Mais, Unario, 7, Esquerda),
+            (TokenKind::Menos, Unario, 7, Esquerda),
+            (TokenKind::Nao, Unario, 7, Esquerda),
+            (TokenKind::Potencia, Binario, 8, Direita),
+        ];
+        padrao
+            .into_iter()
+            .fold(Self::vazia(), |tabela, (simbolo, aridade, precedencia, associatividade)| {
+                tabela.com(simbolo, aridade, precedencia, associatividade)
+            })
+    }
+}
+
+impl OperatorTable {
+    /// A tabela padrão, com a precedência usual dos operadores.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uma tabela sem nenhum operador, para montar uma do zero.
+    pub fn vazia() -> Self {
+        Self { entradas: Vec::new() }
+    }
+
+    /// Registra o operador e devolve a tabela, para montá-la em uma única expressão.
+    pub fn com(
+        mut self,
+        simbolo: TokenKind,
+        aridade: OperatorArity,
+        precedencia: u8,
+        associatividade: Associativity,
+    ) -> Self {
+        self.registrar(simbolo, aridade, precedencia, associatividade);
+        self
+    }
+
+    /// Registra o operador, substituindo o de mesmo símbolo e aridade;
+    /// devolve `true` se ele já existia.
+    pub fn registrar(
+        &mut self,
+        simbolo: TokenKind,
+        aridade: OperatorArity,
+        precedencia: u8,
+        associatividade: Associativity,
+    ) -> bool {
+        let existia = self.remover(&simbolo, aridade);
+        self.entradas.push(Entrada {
+            simbolo,
+            aridade,
+            precedencia,
+            associatividade,
+        });
+        existia
+    }
+
+    /// Remove o operador; devolve `true` se ele existia.
+    pub fn remover(&mut self, simbolo: &TokenKind, aridade: OperatorArity) -> bool {
+        let antes = self.entradas.len();
+        self.entradas
+            .retain(|entrada| !(entrada.simbolo == *simbolo && entrada.aridade == aridade));
+        self.entradas.len() != antes
+    }
+
+    pub fn precedencia(&self, simbolo: &TokenKind, aridade: OperatorArity) -> Option<u8> {
+        self.entrada(simbolo, aridade).map(|entrada| entrada.precedencia)
+    }
+
+    pub fn associatividade(&self, simbolo: &TokenKind, aridade: OperatorArity) -> Option<Associativity> {
+        self.entrada(simbolo, aridade).map(|entrada| entrada.associatividade)
+    }
+
+    fn entrada(&self, simbolo: &TokenKind, aridade: OperatorArity) -> Option<&Entrada> {
+        self.entradas
+            .iter()
+            .find(|entrada| entrada.simbolo == *simbolo && entrada.aridade == aridade)
+    }
+}
@@ -1,120 +1,510 @@
-use std::collections::VecDeque;
-use crate::ast::lexer::{Token, TokenKind};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use crate::ast::lexer::{TextSpan, Token, TokenKind};
+use crate::ast::operadores::{Associativity, OperatorArity, OperatorTable};
+use crate::ast::valor::booleano;
+use crate::numero::{ErroAritmetico, Number};
 
-#[derive(Debug, Clone)]
-pub enum Node {
-    Numero(i64),
+/// Nó da árvore sintática, com literais já convertidos para o domínio `N`.
+#[derive(Debug)]
+pub enum Node<N = i64> {
+    Numero {
+        valor: N,
+        span: TextSpan,
+    },
+    /// Os literais `verdadeiro` e `falso`.
+    Booleano {
+        valor: bool,
+        span: TextSpan,
+    },
+    /// Uma variável, resolvida no [`Environment`](crate::ast::ambiente::Environment)
+    /// durante a avaliação.
+    Variavel {
+        nome: String,
+        span: TextSpan,
+    },
     BinaryOp {
         op: TokenKind,
-        left: Box<Node>,
-        right: Box<Node>,
+        left: Box<Node<N>>,
+        right: Box<Node<N>>,
+        span: TextSpan,
+    },
+    UnaryOp {
+        op: TokenKind,
+        operand: Box<Node<N>>,
+        span: TextSpan,
+    },
+    /// Uma chamada de função, como `max(a, b)`; o nome só é resolvido na
+    /// avaliação.
+    Chamada {
+        nome: String,
+        args: Vec<Node<N>>,
+        span: TextSpan,
+    },
+    /// Uma condicional, escrita como `se c entao a senao b` ou `c ? a : b`;
+    /// só o ramo escolhido pela condição é avaliado.
+    Condicional {
+        condicao: Box<Node<N>>,
+        entao: Box<Node<N>>,
+        senao: Box<Node<N>>,
+        span: TextSpan,
     },
 }
 
-impl Node {
-    // Método para obter o valor de um nó de número
+impl<N: Number> Node<N> {
+    /// Trecho da entrada que originou este nó.
+    pub fn span(&self) -> &TextSpan {
+        match self {
+            Node::Numero { span, .. }
+            | Node::Booleano { span, .. }
+            | Node::Variavel { span, .. }
+            | Node::BinaryOp { span, .. }
+            | Node::UnaryOp { span, .. }
+            | Node::Chamada { span, .. }
+            | Node::Condicional { span, .. } => span,
+        }
+    }
 
-    pub fn avaliar(&self, arvore_str: &mut String) -> i64 {
+    fn span_mut(&mut self) -> &mut TextSpan {
         match self {
-            Node::Numero(val) => *val,
-            Node::BinaryOp { op, left, right } => {
-                let left_val = left.avaliar(arvore_str);
-                let right_val = right.avaliar(arvore_str);
-
-                // Cria uma string representando a expressão atual
-                let op_str = format!(
-                    "({} {} {})",
-                    left_val.to_string(),
-                    match op {
-                        TokenKind::Mais => "+",
-                        TokenKind::Menos => "-",
-                        TokenKind::Asterisco => "*",
-                        TokenKind::Barra => "/",
-                        _ => panic!("Operador inesperado"),
-                    },
-                    right_val.to_string()
-                );
-
-                let resultado = match op {
-                    TokenKind::Mais => left_val + right_val,
-                    TokenKind::Menos => left_val - right_val,
-                    TokenKind::Asterisco => left_val * right_val,
-                    TokenKind::Barra => left_val / right_val,
-                    _ => panic!("Operador não suportado"),
-                };
-
-
-                // Verifica se a expressão está na string antes da substituição
-                if arvore_str.contains(&op_str) {
-                    // Substitui a expressão atual pela avaliação
-                    *arvore_str = arvore_str.replace(&op_str, &resultado.to_string());
-
-                    // Imprime a árvore atualizada
-                } else {
-                    // Adiciona mensagens de depuração detalhadas
-                    println!("Erro: A expressão '{}' não foi encontrada na string da árvore '{}'", op_str, arvore_str);
+            Node::Numero { span, .. }
+            | Node::Booleano { span, .. }
+            | Node::Variavel { span, .. }
+            | Node::BinaryOp { span, .. }
+            | Node::UnaryOp { span, .. }
+            | Node::Chamada { span, .. }
+            | Node::Condicional { span, .. } => span,
+        }
+    }
+
+    pub fn para_string(&self) -> String {
+        let forma: Forma<Node<N>> = match self {
+            Node::Numero { valor, .. } => Forma::Folha(valor.to_string()),
+            Node::Booleano { valor, .. } => Forma::Folha(booleano(*valor).to_string()),
+            Node::Variavel { nome, .. } => Forma::Folha(nome.clone()),
+            Node::BinaryOp { .. } => {
+                // Desce pela cadeia sem recursão, como em "1 + 2 + 3"
+                let mut operacoes = Vec::new();
+                let mut esquerda = self;
+                while let Node::BinaryOp { op, left, right, .. } = esquerda {
+                    operacoes.push((op, right.as_ref()));
+                    esquerda = left;
                 }
-                println!("{}", arvore_str);
-                resultado
+                operacoes.reverse();
+                Forma::Cadeia { esquerda, operacoes }
             }
+            Node::UnaryOp { op, operand, .. } => Forma::Unaria { op, operando: operand },
+            Node::Chamada { nome, args, .. } => Forma::Chamada { nome, argumentos: args },
+            Node::Condicional { condicao, entao, senao, .. } => Forma::Condicional { condicao, entao, senao },
+        };
+        forma.imprimir(Node::para_string)
+    }
+
+}
+
+// Cadeias à esquerda, como a de uma soma de muitas parcelas, são copiadas
+// sem recursão
+impl<N: Clone> Clone for Node<N> {
+    fn clone(&self) -> Self {
+        let mut cadeia = Vec::new();
+        let mut esquerda = self;
+        while let Node::BinaryOp { op, left, right, span } = esquerda {
+            cadeia.push((op, right, span));
+            esquerda = left;
         }
+        let mut copia = match esquerda {
+            Node::Numero { valor, span } => Node::Numero { valor: valor.clone(), span: span.clone() },
+            Node::Booleano { valor, span } => Node::Booleano { valor: *valor, span: span.clone() },
+            Node::Variavel { nome, span } => Node::Variavel { nome: nome.clone(), span: span.clone() },
+            Node::BinaryOp { .. } => unreachable!("a cadeia termina no primeiro nó que não é binário"),
+            Node::UnaryOp { op, operand, span } => Node::UnaryOp {
+                op: op.clone(),
+                operand: operand.clone(),
+                span: span.clone(),
+            },
+            Node::Chamada { nome, args, span } => Node::Chamada {
+                nome: nome.clone(),
+                args: args.clone(),
+                span: span.clone(),
+            },
+            Node::Condicional { condicao, entao, senao, span } => Node::Condicional {
+                condicao: condicao.clone(),
+                entao: entao.clone(),
+                senao: senao.clone(),
+                span: span.clone(),
+            },
+        };
+        for (op, right, span) in cadeia.into_iter().rev() {
+            copia = Node::BinaryOp {
+                op: op.clone(),
+                left: Box::new(copia),
+                right: right.clone(),
+                span: span.clone(),
+            };
+        }
+        copia
     }
+}
 
-    pub fn para_string(&self) -> String {
+// Os filhos são desmontados com uma pilha própria, para que descartar uma
+// árvore longa ou montada à mão não esgote a pilha
+impl<N> Drop for Node<N> {
+    fn drop(&mut self) {
+        let mut pendentes = Vec::new();
+        self.soltar_filhos(&mut pendentes);
+        while let Some(mut no) = pendentes.pop() {
+            no.soltar_filhos(&mut pendentes);
+        }
+    }
+}
+
+impl<N> Node<N> {
+    // Move para `pendentes` os filhos que têm filhos, deixando folhas no lugar
+    fn soltar_filhos(&mut self, pendentes: &mut Vec<Node<N>>) {
+        let mut soltar = |filho: &mut Node<N>| {
+            if !matches!(filho, Node::Numero { .. } | Node::Booleano { .. } | Node::Variavel { .. }) {
+                let folha = Node::Booleano { valor: false, span: TextSpan::new(0, 0, String::new()) };
+                pendentes.push(std::mem::replace(filho, folha));
+            }
+        };
         match self {
-            Node::Numero(val) => val.to_string(),
-            Node::BinaryOp { op, left, right } => {
-                let left_str = left.para_string();
-                let right_str = right.para_string();
+            Node::Numero { .. } | Node::Booleano { .. } | Node::Variavel { .. } => {}
+            Node::BinaryOp { left, right, .. } => {
+                soltar(left);
+                soltar(right);
+            }
+            Node::UnaryOp { operand, .. } => soltar(operand),
+            Node::Chamada { args, .. } => args.iter_mut().for_each(soltar),
+            Node::Condicional { condicao, entao, senao, .. } => {
+                soltar(condicao);
+                soltar(entao);
+                soltar(senao);
+            }
+        }
+    }
+}
 
-                // Adiciona parênteses para garantir a ordem correta das operações
-                format!("({} {} {})", left_str, op, right_str)
+// Como um nó aparece ao ser impresso; a árvore e o rastro da avaliação
+// descrevem seus nós assim para que sejam mostrados da mesma forma
+pub(crate) enum Forma<'a, T> {
+    Folha(String),
+    // Operações binárias encadeadas à esquerda, da mais interna para a mais
+    // externa, cada uma com o seu lado direito
+    Cadeia { esquerda: &'a T, operacoes: Vec<(&'a TokenKind, &'a T)> },
+    Unaria { op: &'a TokenKind, operando: &'a T },
+    Chamada { nome: &'a str, argumentos: &'a [T] },
+    Condicional { condicao: &'a T, entao: &'a T, senao: &'a T },
+}
+
+impl<T> Forma<'_, T> {
+    // Imprime o nó, usando `imprimir` para os filhos
+    pub(crate) fn imprimir(self, imprimir: impl Fn(&T) -> String) -> String {
+        match self {
+            Forma::Folha(texto) => texto,
+            // Adiciona parênteses para garantir a ordem correta das operações
+            Forma::Cadeia { esquerda, operacoes } => {
+                // Os parênteses de todas as operações abrem antes do lado esquerdo
+                let mut texto = "(".repeat(operacoes.len());
+                texto.push_str(&imprimir(esquerda));
+                for (op, direita) in operacoes {
+                    texto.push_str(&format!(" {} {})", op, imprimir(direita)));
+                }
+                texto
+            }
+            Forma::Unaria { op, operando } => {
+                let operando = imprimir(operando);
+                // Evita juntar dois sinais, como em "--5"
+                if operando.starts_with(['-', '+']) {
+                    format!("{}({})", op, operando)
+                } else {
+                    format!("{}{}", op, operando)
+                }
             }
+            Forma::Chamada { nome, argumentos } => {
+                let argumentos: Vec<String> = argumentos.iter().map(imprimir).collect();
+                format!("{}({})", nome, argumentos.join(", "))
+            }
+            Forma::Condicional { condicao, entao, senao } => format!(
+                "(se {} entao {} senao {})",
+                imprimir(condicao),
+                imprimir(entao),
+                imprimir(senao)
+            ),
+        }
+    }
+}
+
+/// Uma instrução de um programa: uma expressão, uma atribuição
+/// `nome = expressão` ou a definição de uma função `nome(x, y) = expressão`.
+#[derive(Debug, Clone)]
+pub enum Statement<N = i64> {
+    Expressao(Node<N>),
+    Atribuicao {
+        nome: String,
+        valor: Node<N>,
+        span: TextSpan,
+    },
+    /// Uma definição não produz valor; o corpo só é avaliado nas chamadas.
+    Definicao {
+        nome: String,
+        parametros: Vec<String>,
+        corpo: Node<N>,
+        span: TextSpan,
+    },
+}
+
+impl<N: Number> Statement<N> {
+    pub fn span(&self) -> &TextSpan {
+        match self {
+            Statement::Expressao(expressao) => expressao.span(),
+            Statement::Atribuicao { span, .. } | Statement::Definicao { span, .. } => span,
+        }
+    }
+
+    /// A expressão calculada pela instrução; numa atribuição, o valor
+    /// atribuído, e numa definição, o corpo da função.
+    pub fn expressao(&self) -> &Node<N> {
+        match self {
+            Statement::Expressao(expressao)
+            | Statement::Atribuicao { valor: expressao, .. }
+            | Statement::Definicao { corpo: expressao, .. } => expressao,
         }
     }
 
+    pub fn para_string(&self) -> String {
+        match self {
+            Statement::Expressao(expressao) => expressao.para_string(),
+            Statement::Atribuicao { nome, valor, .. } => format!("{} = {}", nome, valor.para_string()),
+            Statement::Definicao { nome, parametros, corpo, .. } => {
+                format!("{}({}) = {}", nome, parametros.join(", "), corpo.para_string())
+            }
+        }
+    }
 }
 
+/// Um programa: as instruções na ordem em que aparecem na entrada,
+/// separadas por `;` ou por quebras de linha.
 #[derive(Debug)]
-pub struct Ast {
-    raiz: Option<Node>,
+pub struct Ast<N = i64> {
+    instrucoes: Vec<Statement<N>>,
 }
 
-impl Ast {
-    pub fn nova(raiz: Option<Node>) -> Self {
-        Self { raiz }
+impl<N> Ast<N> {
+    /// Um programa com uma única expressão, ou vazio.
+    pub fn nova(raiz: Option<Node<N>>) -> Self {
+        Self::com_instrucoes(raiz.into_iter().map(Statement::Expressao).collect())
     }
 
-    pub fn raiz(&self) -> Option<&Node> {
-        self.raiz.as_ref()
+    pub fn com_instrucoes(instrucoes: Vec<Statement<N>>) -> Self {
+        Self { instrucoes }
     }
 
-    pub fn eval_step(&self) -> i64 {
-        // Cria uma string mutável para a árvore de expressão
-        let mut arvore_str = self.raiz.as_ref().map_or("".to_string(), |node| node.para_string());
+    pub fn instrucoes(&self) -> &[Statement<N>] {
+        &self.instrucoes
+    }
+
+    /// A expressão da última instrução, cujo valor é o valor do programa.
+    ///
+    /// Definições de funções não têm valor e são ignoradas.
+    pub fn raiz(&self) -> Option<&Node<N>> {
+        self.instrucoes.iter().rev().find_map(|instrucao| match instrucao {
+            Statement::Expressao(expressao) | Statement::Atribuicao { valor: expressao, .. } => Some(expressao),
+            Statement::Definicao { .. } => None,
+        })
+    }
+}
 
-        // Avalia a expressão e imprime passo a passo
-        self.raiz.as_ref().map_or(0, |node| node.avaliar(&mut arvore_str))
+/// Erro produzido por [`Parser::parse`] quando os tokens não formam uma expressão válida.
+///
+/// Cada variante carrega o token que originou o problema, de modo que o
+/// chamador tenha acesso ao seu [`TextSpan`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// Um token apareceu em uma posição onde não é permitido.
+    TokenInesperado(Token),
+    /// Um operador ou parêntese ficou sem um dos seus operandos.
+    OperandoAusente(Token),
+    /// Um parêntese foi aberto sem ser fechado, ou fechado sem ter sido aberto.
+    ParentesesDesbalanceados(Token),
+    /// A expressão já estava completa, mas ainda havia tokens na entrada.
+    EntradaRestante(Token),
+    /// Um literal não pode ser representado no domínio numérico do parser,
+    /// como `2.5` em uma árvore de `i64`.
+    LiteralInvalido(Token, ErroAritmetico),
+    /// O mesmo nome aparece duas vezes entre os parâmetros de uma definição.
+    ParametroRepetido(Token),
+    /// Uma condicional terminou antes da palavra ou do símbolo que separa
+    /// as suas partes, como o `senao` em `se x > 0 entao x`.
+    CondicionalIncompleta(Token, TokenKind),
+    /// A expressão passou de [`PROFUNDIDADE_MAXIMA`] níveis de aninhamento;
+    /// o token é o que abriria mais um nível. Este erro encerra a análise
+    /// mesmo no modo de recuperação.
+    AninhamentoExcessivo(Token),
+}
+
+impl ParseError {
+    pub fn token(&self) -> &Token {
+        match self {
+            ParseError::TokenInesperado(token)
+            | ParseError::OperandoAusente(token)
+            | ParseError::ParentesesDesbalanceados(token)
+            | ParseError::EntradaRestante(token)
+            | ParseError::LiteralInvalido(token, _)
+            | ParseError::ParametroRepetido(token)
+            | ParseError::CondicionalIncompleta(token, _)
+            | ParseError::AninhamentoExcessivo(token) => token,
+        }
+    }
+
+    pub fn span(&self) -> &TextSpan {
+        &self.token().span
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::TokenInesperado(token) => {
+                write!(f, "token inesperado '{}'", token.span.literal)
+            }
+            ParseError::OperandoAusente(token) => match token.kind {
+                TokenKind::FimDeArquivo => write!(f, "operando ausente no fim da expressão"),
+                _ => write!(f, "operando ausente junto a '{}'", token.span.literal),
+            },
+            ParseError::ParentesesDesbalanceados(token) => match token.kind {
+                TokenKind::ParentesesEsquerdo => write!(f, "parêntese aberto sem fechamento"),
+                _ => write!(f, "parêntese fechado sem abertura"),
+            },
+            ParseError::EntradaRestante(token) => {
+                write!(f, "entrada restante após a expressão: '{}'", token.span.literal)
+            }
+            ParseError::LiteralInvalido(token, erro) => {
+                write!(f, "literal inválido '{}': {}", token.span.literal, erro.com_span(&token.span))
+            }
+            ParseError::ParametroRepetido(token) => {
+                write!(f, "parâmetro repetido '{}'", token.span.literal)
+            }
+            ParseError::CondicionalIncompleta(token, esperado) => match token.kind {
+                TokenKind::FimDeArquivo => write!(f, "condicional incompleta: falta '{}' no fim da expressão", esperado),
+                _ => write!(f, "condicional incompleta: falta '{}' antes de '{}'", esperado, token.span.literal),
+            },
+            ParseError::AninhamentoExcessivo(_) => {
+                write!(f, "expressão aninhada demais: mais de {} níveis", PROFUNDIDADE_MAXIMA)
+            }
+        }
     }
+}
+
+impl Error for ParseError {}
+
+/// Quantos níveis uma expressão pode aninhar.
+///
+/// Cada parêntese, operador prefixo, chamada e condicional abre um nível,
+/// assim como o operando direito de um operador binário, de modo que
+/// `2 ^ 2 ^ 2` se aninha a cada `^`. Operações encadeadas à esquerda, como em
+/// `1 + 2 - 3 + 4`, ficam no mesmo nível, e uma soma pode ter quantas
+/// parcelas for preciso. A análise, o rastro e a impressão percorrem
+/// a árvore recursivamente; o limite garante que uma expressão aceita pelo
+/// parser não esgote a pilha, e acima dele o parser devolve
+/// [`ParseError::AninhamentoExcessivo`]. Ele foi medido para caber nos 2 MiB
+/// de pilha de uma thread criada pela biblioteca padrão, mesmo numa
+/// compilação sem otimizações.
+pub const PROFUNDIDADE_MAXIMA: usize = 128;
+
+// O ramo `senao` de um `se` liga mais fraco que qualquer operador
+const PRECEDENCIA_CONDICIONAL: u8 = 0;
 
+// O que encerra um trecho analisado por `analisar_trecho`, além do fim da
+// instrução; fora do contexto de instrução o trecho está aninhado em outra
+// construção, e quebras de linha não o encerram
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Contexto {
+    Instrucao,
+    // O conteúdo de um parêntese, que termina no `)`
+    Parenteses,
+    // Um argumento de chamada, que termina na `,` ou no `)`
+    Argumento,
+    // A condição de um `se`, que termina no `entao`
+    Condicao,
+    // O primeiro ramo de um `se`, que termina no `senao`
+    RamoSe,
+    // O primeiro ramo de um `?`, que termina no `:`
+    RamoTernario,
 }
 
-pub struct Parser {
+impl Contexto {
+    fn delimitado_por(self, kind: &TokenKind) -> bool {
+        match self {
+            Contexto::Instrucao => false,
+            Contexto::Parenteses => *kind == TokenKind::ParentesesDireito,
+            Contexto::Argumento => matches!(kind, TokenKind::Virgula | TokenKind::ParentesesDireito),
+            Contexto::Condicao => *kind == TokenKind::Entao,
+            Contexto::RamoSe => *kind == TokenKind::Senao,
+            Contexto::RamoTernario => *kind == TokenKind::DoisPontos,
+        }
+    }
+
+    fn aninhado(self) -> bool {
+        self != Contexto::Instrucao
+    }
+}
+/// Monta a árvore de um programa, convertendo os literais para o domínio `N`.
+///
+/// Uma instrução termina em `;`, em uma quebra de linha ou no fim da entrada.
+/// Quebras de linha dentro de parênteses ou logo após um operador não
+/// encerram a instrução, de modo que uma expressão longa pode ocupar várias
+/// linhas. Um nome seguido de `(` é uma chamada de função, com os
+/// argumentos separados por `,`. Uma condicional é escrita como
+/// `se c entao a senao b` ou `c ? a : b`, e o último ramo se estende até o
+/// fim da expressão, como em `se x > 0 entao 1 senao x + 1`.
+///
+/// A precedência e a associatividade dos operadores vêm de uma
+/// [`OperatorTable`], a padrão ou a de [`Parser::com_operadores`].
+///
+/// `Parser::new` produz árvores de `i64`; para outro domínio, use
+/// [`Parser::com_dominio`], como em `Parser::<Rational>::com_dominio(tokens)`.
+pub struct Parser<N = i64> {
     tokens: Vec<Token>,
     atual: usize,
+    operadores: OperatorTable,
+    // Em modo de recuperação os erros são acumulados em vez de interromper a análise
+    recuperar: bool,
+    erros: Vec<ParseError>,
+    // Quantas chamadas a `analisar_operacao` estão em andamento; cada uma
+    // corresponde a um nível de aninhamento
+    profundidade: usize,
+    dominio: PhantomData<N>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
+        Self::com_dominio(tokens)
+    }
+}
+
+impl<N: Number> Parser<N> {
+    pub fn com_dominio(tokens: Vec<Token>) -> Self {
         Self {
             tokens,
             atual: 0,
+            operadores: OperatorTable::default(),
+            recuperar: false,
+            erros: Vec::new(),
+            profundidade: 0,
+            dominio: PhantomData,
         }
     }
 
-    fn proximo_token(&mut self) -> Option<&Token> {
+    /// Substitui a tabela de operadores padrão.
+    pub fn com_operadores(mut self, operadores: OperatorTable) -> Self {
+        self.operadores = operadores;
+        self
+    }
+
+    fn proximo_token(&mut self) -> Option<Token> {
         if self.atual < self.tokens.len() {
-            let token = &self.tokens[self.atual];
+            let token = self.tokens[self.atual].clone();
             self.atual += 1;
             Some(token)
         } else {
@@ -122,71 +512,594 @@ impl Parser {
         }
     }
 
-    fn precedencia(op: &TokenKind) -> u8 {
-        match op {
-            TokenKind::Mais | TokenKind::Menos => 1,
-            TokenKind::Asterisco | TokenKind::Barra => 2,
-            _ => 0,
+    // Avança sobre espaços em branco até o próximo token relevante
+    fn proximo_significativo(&mut self) -> Option<Token> {
+        let token = self.proximo_token()?;
+        match token.kind {
+            TokenKind::EspacoEmBranco => self.proximo_significativo(),
+            _ => Some(token),
         }
     }
 
-    pub fn parse(&mut self) -> Ast {
-        let mut saida = VecDeque::new();
-        let mut operadores = Vec::new();
+    fn seguintes(&self) -> impl Iterator<Item = &TokenKind> {
+        self.tokens[self.atual..]
+            .iter()
+            .map(|token| &token.kind)
+            .filter(|kind| **kind != TokenKind::EspacoEmBranco)
+    }
+
+    // O próximo token relevante, sem consumi-lo
+    fn espiar(&self) -> Option<&Token> {
+        self.tokens[self.atual..]
+            .iter()
+            .find(|token| token.kind != TokenKind::EspacoEmBranco)
+    }
+
+    // Token sintético usado para apontar o fim da entrada nos erros
+    fn token_fim(&self) -> Token {
+        let fim = self.tokens.last().map_or(0, |token| token.span.end);
+        Token::new(TokenKind::FimDeArquivo, TextSpan::new(fim, fim, String::new()))
+    }
+
+    // Operadores escritos antes do operando, como `-` e `!`
+    fn prefixo(&self, op: &TokenKind) -> bool {
+        self.operadores.precedencia(op, OperatorArity::Unario).is_some()
+    }
+
+    // Forças de ligação à esquerda e à direita de um operador. O operando
+    // direito absorve os operadores seguintes cuja força à esquerda seja pelo
+    // menos a sua força à direita; por isso um operador associativo à
+    // esquerda liga um pouco mais forte à direita, e `a - b - c` agrupa como
+    // `(a - b) - c`, enquanto `a ^ b ^ c` agrupa como `a ^ (b ^ c)`
+    fn ligacao(precedencia: u8, associatividade: Associativity) -> (u16, u16) {
+        let base = u16::from(precedencia) * 2;
+        match associatividade {
+            Associativity::Esquerda => (base, base + 1),
+            Associativity::Direita => (base + 1, base),
+        }
+    }
+
+    // Tokens que não são operadores binários na tabela não têm ligação
+    fn ligacao_infixa(&self, op: &TokenKind) -> Option<(u16, u16)> {
+        let precedencia = self.operadores.precedencia(op, OperatorArity::Binario)?;
+        let associatividade = self.operadores.associatividade(op, OperatorArity::Binario)?;
+        Some(Self::ligacao(precedencia, associatividade))
+    }
 
-        while let Some(token) = self.proximo_token() {
+    fn ligacao_prefixa(&self, op: &TokenKind) -> u16 {
+        let precedencia = self.operadores.precedencia(op, OperatorArity::Unario).unwrap_or_default();
+        let associatividade = self.operadores.associatividade(op, OperatorArity::Unario).unwrap_or(Associativity::Esquerda);
+        Self::ligacao(precedencia, associatividade).1
+    }
+
+    fn binaria(op: Token, esquerda: Node<N>, direita: Node<N>) -> Node<N> {
+        let span = TextSpan::combinar(vec![esquerda.span().clone(), op.span, direita.span().clone()]);
+        Node::BinaryOp {
+            op: op.kind,
+            left: Box::new(esquerda),
+            right: Box::new(direita),
+            span,
+        }
+    }
+
+    // O token é o `se` ou o `?` que iniciou a condicional
+    fn condicional(token: Token, condicao: Node<N>, entao: Node<N>, senao: Node<N>) -> Node<N> {
+        let span = TextSpan::combinar(vec![
+            token.span,
+            condicao.span().clone(),
+            entao.span().clone(),
+            senao.span().clone(),
+        ]);
+        Node::Condicional {
+            condicao: Box::new(condicao),
+            entao: Box::new(entao),
+            senao: Box::new(senao),
+            span,
+        }
+    }
+    pub fn parse(&mut self) -> Result<Ast<N>, ParseError> {
+        self.recuperar = false;
+        self.analisar()
+    }
+
+    /// Analisa a expressão sem parar no primeiro erro.
+    ///
+    /// Cada problema é registrado e a análise continua como se a entrada
+    /// tivesse sido corrigida no ponto do erro, de modo que todos os
+    /// problemas da expressão sejam devolvidos de uma só vez.
+    pub fn parse_com_recuperacao(&mut self) -> Result<Ast<N>, Vec<ParseError>> {
+        self.recuperar = true;
+        let resultado = self.analisar();
+        let mut erros = std::mem::take(&mut self.erros);
+        match resultado {
+            Ok(ast) if erros.is_empty() => return Ok(ast),
+            Ok(_) => {}
+            Err(erro) => erros.push(erro),
+        }
+        // Erros detectados só no fim, como parênteses não fechados, voltam à ordem da entrada
+        erros.sort_by_key(|erro| erro.span().start);
+        Err(erros)
+    }
+
+    fn falhar(&mut self, erro: ParseError) -> Result<(), ParseError> {
+        if self.recuperar {
+            self.erros.push(erro);
+            Ok(())
+        } else {
+            Err(erro)
+        }
+    }
+
+    fn literal(token: &Token) -> Result<Node<N>, ParseError> {
+        let valor = match &token.kind {
+            TokenKind::Numero(valor) => Ok(N::de_inteiro(*valor)),
+            TokenKind::NumeroGrande(valor) => N::de_inteiro_grande(valor),
+            TokenKind::Decimal(_) => N::de_decimal(&token.span.literal),
+            _ => unreachable!("apenas tokens numéricos viram literais"),
+        };
+        match valor {
+            Ok(valor) => Ok(Node::Numero {
+                valor,
+                span: token.span.clone(),
+            }),
+            Err(erro) => Err(ParseError::LiteralInvalido(token.clone(), erro)),
+        }
+    }
+
+    // Operando fictício usado para seguir em frente após um operando ausente
+    fn operando_substituto(token: &Token) -> Node<N> {
+        let fim = token.span.end;
+        Node::Numero {
+            valor: N::de_inteiro(0),
+            span: TextSpan::new(fim, fim, String::new()),
+        }
+    }
+
+    fn analisar(&mut self) -> Result<Ast<N>, ParseError> {
+        let mut instrucoes = Vec::new();
+        loop {
+            // Instruções vazias, como em "x = 1;;" ou em linhas em branco, são ignoradas
+            while matches!(
+                self.seguintes().next(),
+                Some(TokenKind::PontoEVirgula | TokenKind::NovaLinha)
+            ) {
+                self.proximo_significativo();
+            }
+            if matches!(self.seguintes().next(), None | Some(TokenKind::FimDeArquivo)) {
+                break;
+            }
+            instrucoes.push(self.analisar_instrucao()?);
+        }
+
+        if instrucoes.is_empty() {
+            let fim = self.token_fim();
+            self.falhar(ParseError::OperandoAusente(fim.clone()))?;
+            instrucoes.push(Statement::Expressao(Self::operando_substituto(&fim)));
+        }
+        Ok(Ast::com_instrucoes(instrucoes))
+    }
+
+    fn analisar_instrucao(&mut self) -> Result<Statement<N>, ParseError> {
+        if self.inicia_definicao() {
+            return self.analisar_definicao();
+        }
+        // O `=` só faz uma atribuição logo após o nome que inicia a instrução
+        let atribuicao = {
+            let mut seguintes = self.seguintes();
+            matches!(
+                (seguintes.next(), seguintes.next()),
+                (Some(TokenKind::Identificador(_)), Some(TokenKind::Igual))
+            )
+        };
+        if !atribuicao {
+            return Ok(Statement::Expressao(self.analisar_expressao()?));
+        }
+
+        let nome = self.proximo_significativo().unwrap();
+        let igual = self.proximo_significativo().unwrap();
+        let valor = self.analisar_expressao()?;
+        let span = TextSpan::combinar(vec![nome.span.clone(), igual.span, valor.span().clone()]);
+        let TokenKind::Identificador(nome) = nome.kind else {
+            unreachable!("a atribuição começa por um identificador")
+        };
+        Ok(Statement::Atribuicao { nome, valor, span })
+    }
+
+    // Uma definição começa como uma chamada cujos argumentos são só nomes,
+    // seguida de `=`; a forma exata dos parâmetros é conferida depois
+    fn inicia_definicao(&self) -> bool {
+        let mut seguintes = self.seguintes();
+        if !matches!(
+            (seguintes.next(), seguintes.next()),
+            (Some(TokenKind::Identificador(_)), Some(TokenKind::ParentesesEsquerdo))
+        ) {
+            return false;
+        }
+        for kind in seguintes.by_ref() {
+            match kind {
+                TokenKind::Identificador(_) | TokenKind::Virgula => {}
+                TokenKind::ParentesesDireito => break,
+                _ => return false,
+            }
+        }
+        matches!(seguintes.next(), Some(TokenKind::Igual))
+    }
+
+    fn analisar_definicao(&mut self) -> Result<Statement<N>, ParseError> {
+        let nome = self.proximo_significativo().unwrap();
+        self.proximo_significativo();
+        let mut parametros: Vec<String> = Vec::new();
+        // Alterna entre esperar um nome e esperar `,` ou `)`
+        let mut espera_nome = true;
+        loop {
+            let token = self.proximo_significativo().expect("a definição termina em ')'");
             match &token.kind {
-                TokenKind::Numero(val) => {
-                    saida.push_back(Node::Numero(*val));
-                }
-                TokenKind::Mais | TokenKind::Menos | TokenKind::Asterisco | TokenKind::Barra => {
-                    while let Some(op) = operadores.last() {
-                        if Self::precedencia(op) >= Self::precedencia(&token.kind) {
-                            let op = operadores.pop().unwrap();
-                            let direita = saida.pop_back().unwrap();
-                            let esquerda = saida.pop_back().unwrap();
-                            saida.push_back(Node::BinaryOp {
-                                op,
-                                left: Box::new(esquerda),
-                                right: Box::new(direita),
-                            });
-                        } else {
-                            break;
-                        }
+                TokenKind::Identificador(parametro) if espera_nome => {
+                    if parametros.contains(parametro) {
+                        self.falhar(ParseError::ParametroRepetido(token.clone()))?;
                     }
-                    operadores.push(token.kind.clone());
-                }
-                TokenKind::ParentesesEsquerdo => {
-                    operadores.push(token.kind.clone());
+                    parametros.push(parametro.clone());
+                    espera_nome = false;
                 }
+                TokenKind::Virgula if !espera_nome => espera_nome = true,
+                TokenKind::ParentesesDireito if !espera_nome || parametros.is_empty() => break,
                 TokenKind::ParentesesDireito => {
-                    while let Some(op) = operadores.pop() {
-                        if let TokenKind::ParentesesEsquerdo = op {
-                            break;
-                        }
-                        let direita = saida.pop_back().unwrap();
-                        let esquerda = saida.pop_back().unwrap();
-                        saida.push_back(Node::BinaryOp {
-                            op,
-                            left: Box::new(esquerda),
-                            right: Box::new(direita),
-                        });
-                    }
+                    self.falhar(ParseError::OperandoAusente(token))?;
+                    break;
                 }
-                _ => {}
+                _ => self.falhar(ParseError::TokenInesperado(token))?,
             }
         }
+        let igual = self.proximo_significativo().unwrap();
+        let corpo = self.analisar_expressao()?;
+        let span = TextSpan::combinar(vec![nome.span.clone(), igual.span, corpo.span().clone()]);
+        let TokenKind::Identificador(nome) = nome.kind else {
+            unreachable!("a definição começa por um identificador")
+        };
+        Ok(Statement::Definicao {
+            nome,
+            parametros,
+            corpo,
+            span,
+        })
+    }
+
+    fn analisar_expressao(&mut self) -> Result<Node<N>, ParseError> {
+        Ok(self.analisar_trecho(Contexto::Instrucao)?.0)
+    }
+
+    // Analisa uma expressão completa. Dentro de parênteses, de uma chamada ou
+    // de uma condicional, a expressão termina no delimitador do contexto,
+    // como a `,` ou o `)` que não pertencem a um argumento; esse delimitador
+    // é consumido e devolvido junto com o nó e a sua altura
+    fn analisar_trecho(&mut self, contexto: Contexto) -> Result<(Node<N>, usize, Option<Token>), ParseError> {
+        let (no, altura) = self.analisar_operacao(0, contexto)?;
+        let fim = self.espiar().map(|token| token.kind.clone());
+        let delimitador = match fim {
+            Some(kind) if contexto.delimitado_por(&kind) => self.proximo_significativo(),
+            // Um trecho aninhado deixa o `;` para encerrar a instrução
+            Some(TokenKind::PontoEVirgula | TokenKind::NovaLinha) if !contexto.aninhado() => {
+                self.proximo_significativo();
+                None
+            }
+            _ => None,
+        };
+        Ok((no, altura, delimitador))
+    }
 
-        while let Some(op) = operadores.pop() {
-            let direita = saida.pop_back().unwrap();
-            let esquerda = saida.pop_back().unwrap();
-            saida.push_back(Node::BinaryOp {
-                op,
-                left: Box::new(esquerda),
-                right: Box::new(direita),
-            });
+    // Analisa um operando seguido dos operadores binários que ligam pelo
+    // menos com a força `minima`, cada um com o seu operando direito
+    // analisado recursivamente, e devolve a árvore com a sua altura em
+    // níveis de aninhamento. Novos operadores binários só precisam de uma
+    // entrada na `OperatorTable`
+    fn analisar_operacao(&mut self, minima: u16, contexto: Contexto) -> Result<(Node<N>, usize), ParseError> {
+        // A profundidade é conferida antes de descer, para que a própria
+        // análise não esgote a pilha; a altura dos nós montados sem descer,
+        // como os operandos direitos de "1 + 2 * 3", é conferida ao montar cada nó
+        if self.profundidade >= PROFUNDIDADE_MAXIMA {
+            let token = self.espiar().cloned().unwrap_or_else(|| self.token_fim());
+            return Err(ParseError::AninhamentoExcessivo(token));
         }
+        // Um erro devolvido no meio encerra a análise, e a profundidade deixa de importar
+        self.profundidade += 1;
+        let (mut esquerda, mut altura) = self.analisar_operando(contexto)?;
+        while let Some(token) = self.espiar().cloned() {
+            if contexto.delimitado_por(&token.kind) {
+                break;
+            }
+            // Um operando logo após outro: recupera como se houvesse um `*` entre
+            // os dois. Sinais como `-` são operadores binários nesta posição
+            let implicito = self.ligacao_infixa(&token.kind).is_none() && self.inicia_operando(&token.kind);
+            let op = if implicito {
+                Self::operador_substituto(&token)
+            } else {
+                token.clone()
+            };
+            let Some((forca, direita)) = self.ligacao_infixa(&op.kind) else {
+                if self.descartar(token, contexto)? {
+                    continue;
+                }
+                break;
+            };
+            if forca < minima {
+                break;
+            }
+            if implicito {
+                self.falhar(Self::fora_de_lugar(token, contexto))?;
+            } else {
+                self.proximo_significativo();
+            }
+            (esquerda, altura) = self.analisar_infixo(op, esquerda, altura, direita, contexto)?;
+        }
+        self.profundidade -= 1;
+        Ok((esquerda, altura))
+    }
 
-        Ast::nova(saida.pop_back())
+    // A altura de um nó formado pelo token sobre filhos da altura dada
+    fn aninhar(token: &Token, altura: usize) -> Result<usize, ParseError> {
+        if altura >= PROFUNDIDADE_MAXIMA {
+            return Err(ParseError::AninhamentoExcessivo(token.clone()));
+        }
+        Ok(altura + 1)
+    }
+
+    // Trata um token que não é operador na posição de um operador: devolve
+    // `false` se ele encerra a expressão, ou o descarta, registrando o erro
+    // se houver um
+    fn descartar(&mut self, token: Token, contexto: Contexto) -> Result<bool, ParseError> {
+        match token.kind {
+            TokenKind::PontoEVirgula | TokenKind::FimDeArquivo => return Ok(false),
+            // A quebra de linha só encerra a instrução quando a expressão
+            // está completa e fora de parênteses, de chamadas e de condicionais
+            TokenKind::NovaLinha if !contexto.aninhado() => return Ok(false),
+            TokenKind::NovaLinha => {}
+            // Recupera ignorando o parêntese excedente
+            TokenKind::ParentesesDireito => self.falhar(ParseError::ParentesesDesbalanceados(token))?,
+            _ => self.falhar(ParseError::TokenInesperado(token))?,
+        }
+        self.proximo_significativo();
+        Ok(true)
+    }
+    // Completa a operação iniciada pelo operador binário já consumido
+    fn analisar_infixo(
+        &mut self,
+        op: Token,
+        esquerda: Node<N>,
+        altura: usize,
+        direita: u16,
+        contexto: Contexto,
+    ) -> Result<(Node<N>, usize), ParseError> {
+        if op.kind != TokenKind::Interrogacao {
+            let (operando, altura_direita) = self.analisar_operacao(direita, contexto)?;
+            // Uma operação sobre outra à esquerda continua a mesma cadeia, que
+            // os percursos da árvore seguem sem recursão
+            let altura = if matches!(esquerda, Node::BinaryOp { .. }) {
+                altura.max(Self::aninhar(&op, altura_direita)?)
+            } else {
+                Self::aninhar(&op, altura.max(altura_direita))?
+            };
+            return Ok((Self::binaria(op, esquerda, operando), altura));
+        }
+        // A condição do `?` é tudo o que vem antes dele
+        match self.analisar_parte(Contexto::RamoTernario, TokenKind::DoisPontos)? {
+            Some((entao, altura_entao)) => {
+                let (senao, altura_senao) = self.analisar_operacao(direita, contexto)?;
+                let altura = Self::aninhar(&op, altura.max(altura_entao).max(altura_senao))?;
+                Ok((Self::condicional(op, esquerda, entao, senao), altura))
+            }
+            // Recupera mantendo só a condição
+            None => Ok((esquerda, altura)),
+        }
+    }
+
+    fn inicia_operando(&self, kind: &TokenKind) -> bool {
+        self.prefixo(kind)
+            || matches!(
+                kind,
+                TokenKind::Numero(_)
+                    | TokenKind::NumeroGrande(_)
+                    | TokenKind::Decimal(_)
+                    | TokenKind::Booleano(_)
+                    | TokenKind::Identificador(_)
+                    | TokenKind::ParentesesEsquerdo
+                    | TokenKind::Se
+            )
+    }
+
+    // Analisa um operando: um literal, uma variável, uma chamada, uma
+    // expressão entre parênteses, uma condicional ou um operador prefixo
+    // aplicado a outro operando. Cada forma fica em uma função própria, para
+    // que a recursão em expressões muito aninhadas use pouca pilha
+    fn analisar_operando(&mut self, contexto: Contexto) -> Result<(Node<N>, usize), ParseError> {
+        loop {
+            let token = self.espiar().cloned().unwrap_or_else(|| self.token_fim());
+            if !self.inicia_operando(&token.kind) {
+                match self.operando_ausente(token, contexto)? {
+                    Some(substituto) => return Ok((substituto, 1)),
+                    None => continue,
+                }
+            }
+            self.proximo_significativo();
+            return match token.kind {
+                TokenKind::ParentesesEsquerdo => self.analisar_grupo(token),
+                TokenKind::Se => self.analisar_se(token, contexto),
+                TokenKind::Identificador(_) => self.analisar_nome(token),
+                ref kind if self.prefixo(kind) => self.analisar_prefixo(token, contexto),
+                _ => Ok((self.analisar_literal(token)?, 1)),
+            };
+        }
+    }
+
+    // Trata um token que não inicia um operando na posição de um operando:
+    // descarta os que estão fora de lugar e devolve `None`, ou registra a
+    // falta do operando e devolve um operando fictício, sem consumir o
+    // operador binário, o delimitador ou o fim da instrução encontrado
+    fn operando_ausente(&mut self, token: Token, contexto: Contexto) -> Result<Option<Node<N>>, ParseError> {
+        let delimitador = contexto.delimitado_por(&token.kind);
+        match token.kind {
+            // Quebras de linha logo após um operador não encerram a instrução
+            TokenKind::NovaLinha => {}
+            TokenKind::ParentesesDireito if !delimitador => {
+                self.falhar(ParseError::ParentesesDesbalanceados(token))?
+            }
+            TokenKind::Igual
+            | TokenKind::Virgula
+            | TokenKind::Entao
+            | TokenKind::Senao
+            | TokenKind::DoisPontos
+            | TokenKind::Erro
+                if !delimitador =>
+            {
+                self.falhar(ParseError::TokenInesperado(token))?
+            }
+            _ => {
+                self.falhar(ParseError::OperandoAusente(token.clone()))?;
+                return Ok(Some(Self::operando_substituto(&token)));
+            }
+        }
+        self.proximo_significativo();
+        Ok(None)
+    }
+
+    fn analisar_literal(&mut self, token: Token) -> Result<Node<N>, ParseError> {
+        match token.kind {
+            TokenKind::Booleano(valor) => Ok(Node::Booleano { valor, span: token.span }),
+            _ => match Self::literal(&token) {
+                Ok(literal) => Ok(literal),
+                Err(erro) => {
+                    self.falhar(erro)?;
+                    Ok(Self::operando_substituto(&token))
+                }
+            },
+        }
+    }
+
+    // Um nome seguido de parêntese é uma chamada de função; sozinho, é uma variável
+    fn analisar_nome(&mut self, token: Token) -> Result<(Node<N>, usize), ParseError> {
+        if matches!(self.seguintes().next(), Some(TokenKind::ParentesesEsquerdo)) {
+            return self.analisar_chamada(token);
+        }
+        let TokenKind::Identificador(nome) = token.kind else {
+            unreachable!("apenas identificadores são nomes")
+        };
+        Ok((Node::Variavel { nome, span: token.span }, 1))
+    }
+
+    fn analisar_prefixo(&mut self, op: Token, contexto: Contexto) -> Result<(Node<N>, usize), ParseError> {
+        let (operando, altura) = self.analisar_operacao(self.ligacao_prefixa(&op.kind), contexto)?;
+        let altura = Self::aninhar(&op, altura)?;
+        let span = TextSpan::combinar(vec![op.span, operando.span().clone()]);
+        let no = Node::UnaryOp {
+            op: op.kind,
+            operand: Box::new(operando),
+            span,
+        };
+        Ok((no, altura))
+    }
+    // Analisa uma expressão entre parênteses, a partir do `(`; o span do nó
+    // agrupado passa a incluir os próprios parênteses
+    fn analisar_grupo(&mut self, abertura: Token) -> Result<(Node<N>, usize), ParseError> {
+        let (mut no, altura, fechamento) = self.analisar_trecho(Contexto::Parenteses)?;
+        match fechamento {
+            Some(fechamento) => {
+                let span = no.span_mut();
+                *span = TextSpan::combinar(vec![abertura.span, span.clone(), fechamento.span]);
+            }
+            // Recupera como se o parêntese tivesse sido fechado no fim da expressão
+            None => self.falhar(ParseError::ParentesesDesbalanceados(abertura))?,
+        }
+        Ok((no, altura))
+    }
+    // Analisa os argumentos de uma chamada, a partir do `(` que segue o nome
+    fn analisar_chamada(&mut self, nome: Token) -> Result<(Node<N>, usize), ParseError> {
+        let abertura = self.proximo_significativo().expect("a chamada começa por um parêntese");
+        let mut args = Vec::new();
+        let mut altura = 0;
+        let vazia = matches!(
+            self.seguintes().find(|kind| **kind != TokenKind::NovaLinha),
+            Some(TokenKind::ParentesesDireito)
+        );
+        let fechamento = if vazia {
+            loop {
+                let token = self.proximo_significativo().expect("o parêntese de fechamento foi visto adiante");
+                if token.kind == TokenKind::ParentesesDireito {
+                    break Some(token);
+                }
+            }
+        } else {
+            loop {
+                let (arg, altura_arg, delimitador) = self.analisar_trecho(Contexto::Argumento)?;
+                altura = altura.max(altura_arg);
+                args.push(arg);
+                match delimitador {
+                    Some(token) if token.kind == TokenKind::Virgula => continue,
+                    delimitador => break delimitador,
+                }
+            }
+        };
+
+        let mut spans = vec![nome.span.clone(), abertura.span.clone()];
+        spans.extend(args.iter().map(|arg| arg.span().clone()));
+        match fechamento {
+            Some(fechamento) => spans.push(fechamento.span),
+            // Recupera como se a chamada tivesse sido fechada no fim do último argumento
+            None => self.falhar(ParseError::ParentesesDesbalanceados(abertura))?,
+        }
+        let altura = Self::aninhar(&nome, altura)?;
+        let TokenKind::Identificador(nome) = nome.kind else {
+            unreachable!("a chamada começa por um identificador")
+        };
+        let no = Node::Chamada {
+            nome,
+            args,
+            span: TextSpan::combinar(spans),
+        };
+        Ok((no, altura))
+    }
+
+    // Analisa uma condicional a partir da palavra `se`; o ramo `senao`, como
+    // o último operando de um `?`, se estende até o fim da expressão
+    fn analisar_se(&mut self, se: Token, contexto: Contexto) -> Result<(Node<N>, usize), ParseError> {
+        // Recupera com um operando fictício no lugar da condicional incompleta
+        let Some((condicao, altura_condicao)) = self.analisar_parte(Contexto::Condicao, TokenKind::Entao)? else {
+            return Ok((Self::operando_substituto(&se), 1));
+        };
+        let Some((entao, altura_entao)) = self.analisar_parte(Contexto::RamoSe, TokenKind::Senao)? else {
+            return Ok((Self::operando_substituto(&se), 1));
+        };
+        let (_, direita) = Self::ligacao(PRECEDENCIA_CONDICIONAL, Associativity::Direita);
+        let (senao, altura_senao) = self.analisar_operacao(direita, contexto)?;
+        let altura = Self::aninhar(&se, altura_condicao.max(altura_entao).max(altura_senao))?;
+        Ok((Self::condicional(se, condicao, entao, senao), altura))
+    }
+
+    // Analisa uma parte de uma condicional até o seu delimitador; se a
+    // instrução terminar antes dele, registra o erro e devolve `None`
+    fn analisar_parte(&mut self, contexto: Contexto, esperado: TokenKind) -> Result<Option<(Node<N>, usize)>, ParseError> {
+        match self.analisar_trecho(contexto)? {
+            (no, altura, Some(_)) => Ok(Some((no, altura))),
+            (_, _, None) => {
+                let seguinte = self.espiar().cloned().unwrap_or_else(|| self.token_fim());
+                self.falhar(ParseError::CondicionalIncompleta(seguinte, esperado))?;
+                Ok(None)
+            }
+        }
+    }
+
+    // Operador fictício usado para seguir em frente após dois operandos seguidos
+    fn operador_substituto(token: &Token) -> Token {
+        let inicio = token.span.start;
+        Token::new(TokenKind::Asterisco, TextSpan::new(inicio, inicio, String::new()))
+    }
+
+    // Um operando apareceu logo após outro operando: fora de parênteses, de
+    // chamadas e de condicionais isso significa que a expressão terminou e
+    // sobrou entrada
+    fn fora_de_lugar(token: Token, contexto: Contexto) -> ParseError {
+        if contexto.aninhado() {
+            ParseError::TokenInesperado(token)
+        } else {
+            ParseError::EntradaRestante(token)
+        }
     }
 }
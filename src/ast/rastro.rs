@@ -0,0 +1,401 @@
+use std::collections::VecDeque;
+use crate::ast::ambiente::{Environment, UserFunction};
+use crate::ast::avaliador::{aplicar_unario, curto_circuito, variavel, verificar_chamada, EvalError, Evaluator};
+use crate::ast::lexer::{TextSpan, TokenKind};
+use crate::ast::parser::{Ast, Forma, Node, Statement};
+use crate::ast::valor::Value;
+use crate::numero::Number;
+
+/// Uma operação ou chamada de função resolvida durante a avaliação passo a passo.
+///
+/// Sinais unários não geram passos próprios: são absorvidos pelo número
+/// ao qual se aplicam assim que ele é conhecido. A chamada de uma função
+/// definida no programa é um único passo, sem as operações do corpo, e um
+/// `&&` ou `||` decidido pelo lado esquerdo é reduzido sem avaliar o direito.
+/// Da mesma forma, só o ramo de uma condicional escolhido pela condição é
+/// reduzido, e a condicional vira o valor desse ramo em um passo próprio.
+#[derive(Debug, Clone)]
+pub struct Step<N = i64> {
+    /// O nó da árvore original que foi reduzido.
+    pub no: Node<N>,
+    /// Os valores dos operandos, ou dos argumentos de uma chamada, da
+    /// esquerda para a direita; numa condicional, a condição e o valor do
+    /// ramo escolhido.
+    pub operandos: Vec<Value<N>>,
+    /// O valor que substituiu o nó na árvore.
+    pub resultado: Value<N>,
+    /// A expressão inteira reescrita após a redução.
+    pub expressao: String,
+}
+
+/// Registro completo de uma avaliação passo a passo.
+///
+/// Os passos são calculados sobre a própria árvore, reduzindo uma operação
+/// por vez na ordem em que a avaliação as resolve. Se uma operação falhar,
+/// `passos` contém tudo o que foi resolvido antes do erro.
+#[derive(Debug, Clone)]
+pub struct EvaluationTrace<N = i64> {
+    /// A expressão antes de qualquer redução.
+    pub expressao: String,
+    pub passos: Vec<Step<N>>,
+    pub resultado: Result<Value<N>, EvalError>,
+}
+
+impl<N: Number> Ast<N> {
+    /// Avalia passo a passo com a configuração padrão.
+    pub fn eval_step(&self) -> EvaluationTrace<N> {
+        Evaluator::default().rastrear(self)
+    }
+}
+
+impl Evaluator {
+    pub fn rastrear<N: Number>(&self, ast: &Ast<N>) -> EvaluationTrace<N> {
+        self.rastrear_em(ast, &Environment::new())
+    }
+
+    /// Avalia passo a passo buscando as variáveis no ambiente.
+    ///
+    /// As instruções são reduzidas em ordem, e cada atribuição ou definição
+    /// vale para as instruções seguintes sem alterar o ambiente recebido.
+    /// Definições aparecem nas expressões sem nunca serem reduzidas. As expressões
+    /// mostram o programa inteiro, com as instruções separadas por `; `, e as
+    /// variáveis aparecem pelo nome até que a operação que as usa seja reduzida.
+    pub fn rastrear_em<N: Number>(&self, ast: &Ast<N>, ambiente: &Environment<N>) -> EvaluationTrace<N> {
+        self.rastrear_executando(ast, &mut ambiente.clone())
+    }
+
+    /// Como [`Evaluator::rastrear_em`], mas as atribuições e definições ficam no
+    /// ambiente, como em [`Evaluator::executar`]; o resultado do rastro é o valor
+    /// do programa. Uma falha deixa no ambiente o que foi feito até ela.
+    pub fn rastrear_executando<N: Number>(&self, ast: &Ast<N>, ambiente: &mut Environment<N>) -> EvaluationTrace<N> {
+        let mut programa: Vec<(&Statement<N>, Termo<N>)> = ast
+            .instrucoes()
+            .iter()
+            .map(|instrucao| {
+                let mut termo = Termo::novo(instrucao.expressao());
+                termo.dobrar_sinais();
+                (instrucao, termo)
+            })
+            .collect();
+        let expressao = self.programa_para_string(&programa);
+        let mut passos = Vec::new();
+        let mut resultado = Value::Numero(N::de_inteiro(0));
+
+        for i in 0..programa.len() {
+            if let Statement::Definicao { nome, parametros, corpo, .. } = programa[i].0 {
+                ambiente.definir_funcao(nome.clone(), UserFunction::new(parametros.clone(), corpo.clone()));
+                continue;
+            }
+            loop {
+                match self.reduzir_proximo(&mut programa[i].1, ambiente) {
+                    Some(Ok((no, operandos, resultado))) => passos.push(Step {
+                        no: no.clone(),
+                        operandos,
+                        resultado,
+                        expressao: self.programa_para_string(&programa),
+                    }),
+                    Some(Err(erro)) => {
+                        return EvaluationTrace {
+                            expressao,
+                            passos,
+                            resultado: Err(erro),
+                        }
+                    }
+                    None => break,
+                }
+            }
+
+            let (instrucao, termo) = &mut programa[i];
+            resultado = match termo.valor(ambiente) {
+                Ok(valor) => valor,
+                Err(erro) => {
+                    return EvaluationTrace {
+                        expressao,
+                        passos,
+                        resultado: Err(erro),
+                    }
+                }
+            };
+            // Uma instrução que era só uma variável passa a mostrar o seu valor
+            *termo = Termo::Valor(resultado.clone());
+            if let Statement::Atribuicao { nome, .. } = instrucao {
+                ambiente.definir(nome.clone(), resultado.clone());
+            }
+        }
+
+        EvaluationTrace {
+            expressao,
+            passos,
+            resultado: Ok(resultado),
+        }
+    }
+
+    fn programa_para_string<N: Number>(&self, programa: &[(&Statement<N>, Termo<N>)]) -> String {
+        let instrucoes: Vec<String> = programa
+            .iter()
+            .map(|(instrucao, termo)| match instrucao {
+                Statement::Expressao(_) => termo.para_string(self),
+                Statement::Atribuicao { nome, .. } => format!("{} = {}", nome, termo.para_string(self)),
+                Statement::Definicao { nome, parametros, .. } => {
+                    format!("{}({}) = {}", nome, parametros.join(", "), termo.para_string(self))
+                }
+            })
+            .collect();
+        instrucoes.join("; ")
+    }
+
+    // Reduz a primeira operação cujos operandos já são valores, percorrendo a
+    // árvore em pós-ordem, e a substitui pelo seu resultado
+    fn reduzir_proximo<'a, N: Number>(
+        &self,
+        termo: &mut Termo<'a, N>,
+        ambiente: &Environment<N>,
+    ) -> Option<Result<Reducao<'a, N>, EvalError>> {
+        let (no, resultado) = match termo {
+            Termo::Valor(_) | Termo::Variavel { .. } => return None,
+            Termo::Binario { esquerda, operacoes } => {
+                if let Some(reducao) = self.reduzir_proximo(esquerda, ambiente) {
+                    return Some(reducao);
+                }
+                // Só a operação mais interna da cadeia tem o lado esquerdo resolvido
+                let (no, op, direita) = operacoes.front_mut().expect("uma cadeia tem pelo menos uma operação");
+                // Um `&&` ou `||` decidido pelo lado esquerdo não reduz o direito
+                match decidir_pela_esquerda(no, op, esquerda, ambiente) {
+                    Ok(Some(reducao)) => (*no, Ok(reducao)),
+                    Ok(None) => {
+                        if let Some(reducao) = self.reduzir_proximo(direita, ambiente) {
+                            return Some(reducao);
+                        }
+                        let operandos = match (esquerda.valor(ambiente), direita.valor(ambiente)) {
+                            (Ok(esquerda), Ok(direita)) => vec![esquerda, direita],
+                            (Err(erro), _) | (_, Err(erro)) => return Some(Err(erro)),
+                        };
+                        let resultado = self
+                            .aplicar_binario(no, &operandos[0], &operandos[1])
+                            .map(|resultado| (operandos, resultado));
+                        (*no, resultado)
+                    }
+                    Err(erro) => return Some(Err(erro)),
+                }
+            }
+            Termo::Unario { no, op, operando } => {
+                let reducao = self.reduzir_proximo(operando, ambiente);
+                if matches!(reducao, Some(Err(_))) || !operando.resolvido() {
+                    return reducao;
+                }
+                // O sinal é absorvido pelo valor assim que o operando é resolvido
+                match operando.valor(ambiente).and_then(|valor| aplicar_unario(op, &valor, no.span())) {
+                    Ok(valor) => {
+                        *termo = Termo::Valor(valor);
+                        return reducao;
+                    }
+                    Err(erro) => return Some(Err(erro)),
+                }
+            }
+            Termo::Chamada { no, nome, argumentos } => {
+                let Node::Chamada { args, span, .. } = no else {
+                    unreachable!("o termo de uma chamada guarda o nó da chamada")
+                };
+                // Uma chamada inválida falha antes que os argumentos sejam reduzidos
+                if let Err(erro) = verificar_chamada(nome, args, span, ambiente) {
+                    return Some(Err(erro));
+                }
+                for argumento in argumentos.iter_mut() {
+                    if let Some(reducao) = self.reduzir_proximo(argumento, ambiente) {
+                        return Some(reducao);
+                    }
+                }
+                let operandos: Vec<Value<N>> = match argumentos.iter().map(|argumento| argumento.valor(ambiente)).collect() {
+                    Ok(operandos) => operandos,
+                    Err(erro) => return Some(Err(erro)),
+                };
+                let resultado = self
+                    .chamar(nome, args, operandos.clone(), span, ambiente)
+                    .map(|resultado| (operandos, resultado));
+                (*no, resultado)
+            }
+            Termo::Condicional { no, condicao, entao, senao } => {
+                if let Some(reducao) = self.reduzir_proximo(condicao, ambiente) {
+                    return Some(reducao);
+                }
+                let Node::Condicional { condicao: no_da_condicao, .. } = no else {
+                    unreachable!("o termo de uma condicional guarda o nó da condicional")
+                };
+                let escolha = match condicao.valor(ambiente) {
+                    Ok(escolha) => escolha,
+                    Err(erro) => return Some(Err(erro)),
+                };
+                let ramo = match escolha.como_booleano(no_da_condicao.span()) {
+                    Ok(true) => entao,
+                    Ok(false) => senao,
+                    Err(erro) => return Some(Err(erro)),
+                };
+                // O ramo escolhido é reduzido dentro da condicional, que só então vira o seu valor
+                if let Some(reducao) = self.reduzir_proximo(ramo, ambiente) {
+                    return Some(reducao);
+                }
+                let resultado = ramo.valor(ambiente).map(|resultado| (vec![escolha, resultado.clone()], resultado));
+                (*no, resultado)
+            }
+        };
+
+        let (operandos, resultado) = match resultado {
+            Ok(reducao) => reducao,
+            Err(erro) => return Some(Err(erro)),
+        };
+        match termo {
+            // O resultado vira o lado esquerdo da operação seguinte da cadeia
+            Termo::Binario { esquerda, operacoes } if operacoes.len() > 1 => {
+                operacoes.pop_front();
+                **esquerda = Termo::Valor(resultado.clone());
+            }
+            _ => *termo = Termo::Valor(resultado.clone()),
+        }
+        Some(Ok((no, operandos, resultado)))
+    }
+}
+
+// O resultado de um `&&` ou `||` cujo lado esquerdo, já resolvido, decide a
+// operação, junto com o valor desse lado
+fn decidir_pela_esquerda<N: Number>(
+    no: &Node<N>,
+    op: &TokenKind,
+    esquerda: &Termo<N>,
+    ambiente: &Environment<N>,
+) -> Result<Option<Operacao<N>>, EvalError> {
+    let (TokenKind::E | TokenKind::Ou, Node::BinaryOp { left, .. }) = (op, no) else {
+        return Ok(None);
+    };
+    let valor = esquerda.valor(ambiente)?;
+    Ok(curto_circuito(op, &valor, left.span())?.map(|resultado| (vec![valor], resultado)))
+}
+
+// Os operandos de um passo e o seu resultado
+type Operacao<N> = (Vec<Value<N>>, Value<N>);
+
+type Reducao<'a, N> = (&'a Node<N>, Vec<Value<N>>, Value<N>);
+
+// A árvore sendo reduzida: cada operação ainda não resolvida guarda o nó
+// original, e as já resolvidas viram valores
+enum Termo<'a, N> {
+    Valor(Value<N>),
+    // As variáveis só são buscadas quando a operação que as usa é reduzida
+    Variavel {
+        nome: &'a str,
+        span: &'a TextSpan,
+    },
+    // Operações binárias encadeadas à esquerda, como em "1 + 2 + 3", ficam
+    // em sequência, da mais interna para a mais externa, cada uma com o seu
+    // nó e o seu lado direito, para que cadeias longas não aprofundem a recursão
+    Binario {
+        esquerda: Box<Termo<'a, N>>,
+        operacoes: VecDeque<(&'a Node<N>, &'a TokenKind, Termo<'a, N>)>,
+    },
+    Unario {
+        no: &'a Node<N>,
+        op: &'a TokenKind,
+        operando: Box<Termo<'a, N>>,
+    },
+    Chamada {
+        no: &'a Node<N>,
+        nome: &'a str,
+        argumentos: Vec<Termo<'a, N>>,
+    },
+    Condicional {
+        no: &'a Node<N>,
+        condicao: Box<Termo<'a, N>>,
+        entao: Box<Termo<'a, N>>,
+        senao: Box<Termo<'a, N>>,
+    },
+}
+
+impl<'a, N: Number> Termo<'a, N> {
+    fn novo(no: &'a Node<N>) -> Self {
+        match no {
+            Node::Numero { valor, .. } => Termo::Valor(Value::Numero(valor.clone())),
+            Node::Booleano { valor, .. } => Termo::Valor(Value::Booleano(*valor)),
+            Node::Variavel { nome, span } => Termo::Variavel { nome, span },
+            Node::BinaryOp { .. } => {
+                let mut operacoes = VecDeque::new();
+                let mut esquerda = no;
+                while let Node::BinaryOp { op, left, right, .. } = esquerda {
+                    operacoes.push_front((esquerda, op, Termo::novo(right)));
+                    esquerda = left;
+                }
+                Termo::Binario {
+                    esquerda: Box::new(Termo::novo(esquerda)),
+                    operacoes,
+                }
+            }
+            Node::UnaryOp { op, operand, .. } => Termo::Unario {
+                no,
+                op,
+                operando: Box::new(Termo::novo(operand)),
+            },
+            Node::Chamada { nome, args, .. } => Termo::Chamada {
+                no,
+                nome,
+                argumentos: args.iter().map(Termo::novo).collect(),
+            },
+            Node::Condicional { condicao, entao, senao, .. } => Termo::Condicional {
+                no,
+                condicao: Box::new(Termo::novo(condicao)),
+                entao: Box::new(Termo::novo(entao)),
+                senao: Box::new(Termo::novo(senao)),
+            },
+        }
+    }
+
+    fn resolvido(&self) -> bool {
+        matches!(self, Termo::Valor(_) | Termo::Variavel { .. })
+    }
+
+    fn valor(&self, ambiente: &Environment<N>) -> Result<Value<N>, EvalError> {
+        match self {
+            Termo::Valor(valor) => Ok(valor.clone()),
+            Termo::Variavel { nome, span } => variavel(nome, span, ambiente),
+            _ => unreachable!("os operandos são reduzidos antes do nó que os contém"),
+        }
+    }
+
+    // Sinais unários não são mostrados como operações próprias: os que estão
+    // sobre literais, como em "-8" ou "--1", já entram no rastro resolvidos
+    fn dobrar_sinais(&mut self) {
+        match self {
+            Termo::Valor(_) | Termo::Variavel { .. } => {}
+            Termo::Binario { esquerda, operacoes } => {
+                esquerda.dobrar_sinais();
+                operacoes.iter_mut().for_each(|(_, _, direita)| direita.dobrar_sinais());
+            }
+            Termo::Unario { no, op, operando } => {
+                operando.dobrar_sinais();
+                if let Termo::Valor(valor) = operando.as_ref() {
+                    if let Ok(valor) = aplicar_unario(op, valor, no.span()) {
+                        *self = Termo::Valor(valor);
+                    }
+                }
+            }
+            Termo::Chamada { argumentos, .. } => argumentos.iter_mut().for_each(Termo::dobrar_sinais),
+            Termo::Condicional { condicao, entao, senao, .. } => {
+                condicao.dobrar_sinais();
+                entao.dobrar_sinais();
+                senao.dobrar_sinais();
+            }
+        }
+    }
+
+    fn para_string(&self, avaliador: &Evaluator) -> String {
+        let forma: Forma<Termo<N>> = match self {
+            Termo::Valor(valor) => Forma::Folha(valor.exibir(avaliador)),
+            Termo::Variavel { nome, .. } => Forma::Folha(nome.to_string()),
+            Termo::Binario { esquerda, operacoes } => Forma::Cadeia {
+                esquerda,
+                operacoes: operacoes.iter().map(|(_, op, direita)| (*op, direita)).collect(),
+            },
+            Termo::Unario { op, operando, .. } => Forma::Unaria { op, operando },
+            Termo::Chamada { nome, argumentos, .. } => Forma::Chamada { nome, argumentos },
+            Termo::Condicional { condicao, entao, senao, .. } => Forma::Condicional { condicao, entao, senao },
+        };
+        forma.imprimir(|termo| termo.para_string(avaliador))
+    }
+}
@@ -0,0 +1,114 @@
+use std::fmt::{Display, Formatter};
+use crate::ast::avaliador::{EvalError, Evaluator};
+use crate::ast::lexer::TextSpan;
+use crate::numero::Number;
+
+/// Resultado da avaliação: um número do domínio `N` ou um booleano, como o
+/// de `idade >= 18 && saldo > 0`.
+///
+/// Operações aritméticas, comparações de ordem e funções numéricas só
+/// aceitam números; `&&`, `||` e `!` só aceitam booleanos. Misturar os dois
+/// resulta em [`EvalError::TipoIncorreto`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<N = i64> {
+    Numero(N),
+    Booleano(bool),
+}
+
+/// O tipo de um [`Value`], usado nos erros de tipo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    Numero,
+    Booleano,
+}
+
+impl Display for ValueType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueType::Numero => write!(f, "número"),
+            ValueType::Booleano => write!(f, "booleano"),
+        }
+    }
+}
+
+impl<N> Value<N> {
+    pub fn tipo(&self) -> ValueType {
+        match self {
+            Value::Numero(_) => ValueType::Numero,
+            Value::Booleano(_) => ValueType::Booleano,
+        }
+    }
+
+    pub fn numero(&self) -> Option<&N> {
+        match self {
+            Value::Numero(numero) => Some(numero),
+            Value::Booleano(_) => None,
+        }
+    }
+
+    pub fn booleano(&self) -> Option<bool> {
+        match self {
+            Value::Booleano(booleano) => Some(*booleano),
+            Value::Numero(_) => None,
+        }
+    }
+}
+
+impl<N: Number> Value<N> {
+    /// Texto usado para o valor nas expressões do rastro.
+    pub fn exibir(&self, avaliador: &Evaluator) -> String {
+        match self {
+            Value::Numero(numero) => numero.exibir(avaliador),
+            Value::Booleano(booleano) => self::booleano(*booleano).to_string(),
+        }
+    }
+
+    // O número do valor, ou um erro de tipo apontando para o trecho que o produziu
+    pub(crate) fn como_numero(&self, span: &TextSpan) -> Result<&N, EvalError> {
+        self.numero().ok_or_else(|| self.erro_de_tipo(ValueType::Numero, span))
+    }
+
+    pub(crate) fn como_booleano(&self, span: &TextSpan) -> Result<bool, EvalError> {
+        self.booleano().ok_or_else(|| self.erro_de_tipo(ValueType::Booleano, span))
+    }
+
+    pub(crate) fn erro_de_tipo(&self, esperado: ValueType, span: &TextSpan) -> EvalError {
+        EvalError::TipoIncorreto {
+            esperado,
+            encontrado: self.tipo(),
+            span: span.clone(),
+        }
+    }
+}
+
+// As palavras reservadas que escrevem cada booleano
+pub(crate) fn booleano(valor: bool) -> &'static str {
+    if valor {
+        "verdadeiro"
+    } else {
+        "falso"
+    }
+}
+
+impl<N> From<N> for Value<N> {
+    fn from(numero: N) -> Self {
+        Value::Numero(numero)
+    }
+}
+
+/// Compara diretamente com um número, como em `valor == 7`; um booleano
+/// nunca é igual a um número.
+impl<N: PartialEq> PartialEq<N> for Value<N> {
+    fn eq(&self, outro: &N) -> bool {
+        matches!(self, Value::Numero(numero) if numero == outro)
+    }
+}
+
+impl<N: Display> Display for Value<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Numero(numero) => write!(f, "{}", numero),
+            Value::Booleano(valor) => write!(f, "{}", booleano(*valor)),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt::Write;
+use crate::ast::ambiente::Environment;
+use crate::ast::avaliador::EvalError;
+use crate::ast::funcoes::EMBUTIDAS;
+use crate::ast::lexer::{LexError, TextSpan, TokenKind};
+use crate::ast::parser::ParseError;
+use crate::ast::valor::ValueType;
+use crate::fonte::SourceMap;
+use crate::numero::Number;
+
+/// Um problema encontrado na entrada, pronto para ser mostrado ao usuário.
+///
+/// A renderização segue o estilo do rustc: a mensagem, a linha da entrada
+/// com o trecho problemático sublinhado por `^` e, quando houver, uma dica.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub mensagem: String,
+    pub span: TextSpan,
+    pub dica: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(mensagem: impl Into<String>, span: TextSpan) -> Self {
+        Self {
+            mensagem: mensagem.into(),
+            span,
+            dica: None,
+        }
+    }
+
+    pub fn com_dica(mut self, dica: impl Into<String>) -> Self {
+        self.dica = Some(dica.into());
+        self
+    }
+
+    /// O diagnóstico de um erro de avaliação, com as dicas completadas pelo
+    /// ambiente em que a avaliação falhou: uma função indefinida lista as
+    /// embutidas, as registradas e as definidas no programa.
+    pub fn de_avaliacao<N: Number>(erro: &EvalError, ambiente: &Environment<N>) -> Self {
+        let diagnostico = Diagnostic::from(erro);
+        let EvalError::FuncaoIndefinida { .. } = erro else {
+            return diagnostico;
+        };
+        let mut nomes = EMBUTIDAS.to_vec();
+        for nome in ambiente.registro().nomes().into_iter().chain(ambiente.nomes_de_funcoes()) {
+            if !nomes.contains(&nome) {
+                nomes.push(nome);
+            }
+        }
+        diagnostico.com_dica(format!("as funções disponíveis são {}", nomes.join(", ")))
+    }
+
+    pub fn renderizar(&self, fonte: &str) -> String {
+        self.renderizar_com(&SourceMap::new(fonte))
+    }
+
+    /// Renderiza o diagnóstico indicando arquivo, linha e coluna do trecho.
+    pub fn renderizar_com(&self, mapa: &SourceMap) -> String {
+        let inicio = mapa.posicao(self.span.start);
+        let fim = mapa.posicao(self.span.end);
+        let linha = mapa.linha(inicio.linha);
+        // Um trecho que atravessa linhas é sublinhado até o fim da primeira
+        let fim_coluna = if fim.linha == inicio.linha {
+            fim.coluna
+        } else {
+            linha.chars().count() + 1
+        };
+        // Spans vazios, como o do fim da entrada, ainda recebem um marcador
+        let largura = fim_coluna.saturating_sub(inicio.coluna).max(1);
+        let numero = inicio.linha.to_string();
+        let margem = " ".repeat(numero.len());
+
+        let mut saida = String::new();
+        writeln!(saida, "erro: {}", self.mensagem).unwrap();
+        writeln!(saida, "{}--> {}", margem, mapa.localizar(&self.span)).unwrap();
+        writeln!(saida, "{} |", margem).unwrap();
+        writeln!(saida, "{} | {}", numero, linha).unwrap();
+        write!(saida, "{} | {}{}", margem, " ".repeat(inicio.coluna - 1), "^".repeat(largura)).unwrap();
+        if let Some(dica) = &self.dica {
+            write!(saida, "\n{} = dica: {}", margem, dica).unwrap();
+        }
+        saida
+    }
+}
+
+impl From<&LexError> for Diagnostic {
+    fn from(erro: &LexError) -> Self {
+        Diagnostic::new(erro.to_string(), erro.span().clone())
+            .com_dica("remova o caractere ou substitua-o por um operador válido")
+    }
+}
+
+const FORMA_DA_CONDICIONAL: &str =
+    "uma condicional tem a forma 'se condição entao valor senao valor' ou 'condição ? valor : valor'";
+
+impl From<&ParseError> for Diagnostic {
+    fn from(erro: &ParseError) -> Self {
+        let diagnostico = Diagnostic::new(erro.to_string(), erro.span().clone());
+        match erro {
+            ParseError::TokenInesperado(token) if token.kind == TokenKind::Erro => {
+                diagnostico.com_dica("remova o caractere ou substitua-o por um operador válido")
+            }
+            ParseError::TokenInesperado(token) if token.kind == TokenKind::Igual => {
+                diagnostico.com_dica("a atribuição deve começar a instrução, como em 'x = 3'")
+            }
+            ParseError::TokenInesperado(token) if token.kind == TokenKind::Virgula => {
+                diagnostico.com_dica("a vírgula só separa os argumentos de uma chamada, como em 'max(1, 2)'")
+            }
+            ParseError::TokenInesperado(token)
+                if matches!(token.kind, TokenKind::Entao | TokenKind::Senao | TokenKind::DoisPontos) =>
+            {
+                diagnostico.com_dica(FORMA_DA_CONDICIONAL)
+            }
+            ParseError::TokenInesperado(_) => {
+                diagnostico.com_dica("verifique se falta um operador antes deste token")
+            }
+            ParseError::OperandoAusente(_) => {
+                diagnostico.com_dica("adicione um número ou uma expressão entre parênteses")
+            }
+            ParseError::ParentesesDesbalanceados(token) if token.kind == TokenKind::ParentesesEsquerdo => {
+                diagnostico.com_dica("feche o parêntese com ')'")
+            }
+            ParseError::ParentesesDesbalanceados(_) => {
+                diagnostico.com_dica("remova este ')' ou adicione o '(' correspondente")
+            }
+            ParseError::EntradaRestante(_) => {
+                diagnostico.com_dica("adicione um operador entre as expressões ou remova o excesso")
+            }
+            ParseError::ParametroRepetido(_) => {
+                diagnostico.com_dica("dê um nome diferente a cada parâmetro")
+            }
+            ParseError::CondicionalIncompleta(..) => diagnostico.com_dica(FORMA_DA_CONDICIONAL),
+            ParseError::AninhamentoExcessivo(_) => {
+                diagnostico.com_dica("divida a expressão em partes menores, guardando cada uma em uma variável")
+            }
+            // A dica depende de por que o domínio recusou o literal
+            ParseError::LiteralInvalido(token, causa) => Diagnostic {
+                mensagem: diagnostico.mensagem,
+                ..Diagnostic::from(&causa.com_span(&token.span))
+            },
+        }
+    }
+}
+
+impl From<&EvalError> for Diagnostic {
+    fn from(erro: &EvalError) -> Self {
+        let diagnostico = Diagnostic::new(erro.to_string(), erro.span().clone());
+        match erro {
+            EvalError::DivisaoPorZero { .. } => {
+                diagnostico.com_dica("o divisor desta operação vale zero")
+            }
+            EvalError::Overflow { .. } => {
+                diagnostico.com_dica("divida o cálculo em partes menores")
+            }
+            EvalError::ExpoenteNegativo { .. } => {
+                diagnostico.com_dica("o resultado não seria inteiro; use um expoente maior ou igual a zero")
+            }
+            EvalError::ExpoenteFracionario { .. } => {
+                diagnostico.com_dica("use a avaliação real para potências com expoente fracionário")
+            }
+            EvalError::LiteralDecimal { .. } => {
+                diagnostico.com_dica("use a avaliação real para trabalhar com números decimais")
+            }
+            EvalError::ResultadoIndefinido { .. } => {
+                diagnostico.com_dica("a operação não tem resultado real, como a raiz de um número negativo")
+            }
+            EvalError::ResultadoIrracional { .. } => {
+                diagnostico.com_dica("use a avaliação real para obter um valor aproximado")
+            }
+            EvalError::OperadorNaoSuportado { .. } => diagnostico,
+            EvalError::VariavelIndefinida { .. } => {
+                diagnostico.com_dica("defina a variável no ambiente antes de avaliar a expressão")
+            }
+            // Sem o ambiente não se sabe quais funções existem; veja `Diagnostic::de_avaliacao`
+            EvalError::FuncaoIndefinida { .. } => {
+                diagnostico.com_dica("verifique o nome, ou defina a função antes de chamá-la")
+            }
+            EvalError::AridadeIncorreta { esperada, recebida, .. } => match esperada.maxima() {
+                Some(maxima) if *recebida > maxima => diagnostico.com_dica("remova os argumentos excedentes"),
+                _ => diagnostico.com_dica("adicione os argumentos que faltam"),
+            },
+            EvalError::TipoIncorreto { esperado: ValueType::Numero, .. } => {
+                diagnostico.com_dica("operações aritméticas, comparações de ordem e funções numéricas só aceitam números")
+            }
+            EvalError::TipoIncorreto { .. } => {
+                diagnostico.com_dica("'&&', '||' e '!' só aceitam booleanos, como o resultado de uma comparação")
+            }
+            EvalError::RecursaoExcessiva { .. } => {
+                diagnostico.com_dica("verifique se a recursão chega a um caso que não chama a própria função")
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt::{Display, Formatter};
+use crate::ast::lexer::TextSpan;
+
+/// Linha e coluna de um ponto da entrada, ambas começando em 1.
+///
+/// A coluna é contada em caracteres, não em bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Posicao {
+    pub linha: usize,
+    pub coluna: usize,
+}
+
+/// Posição de um trecho dentro de um arquivo (ou de uma entrada sem nome).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Localizacao {
+    pub arquivo: Option<String>,
+    pub posicao: Posicao,
+}
+
+impl Display for Localizacao {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(arquivo) = &self.arquivo {
+            write!(f, "{}:", arquivo)?;
+        }
+        write!(f, "{}:{}", self.posicao.linha, self.posicao.coluna)
+    }
+}
+
+/// Converte as posições em bytes dos [`TextSpan`] em linha e coluna.
+///
+/// Os spans produzidos pelo lexer, pelo parser e pelo avaliador são sempre
+/// deslocamentos dentro da entrada; o `SourceMap` guarda onde cada linha
+/// começa para traduzi-los em posições como `calc.txt:12:7`.
+#[derive(Debug, Clone)]
+pub struct SourceMap<'a> {
+    arquivo: Option<String>,
+    fonte: &'a str,
+    inicios_de_linha: Vec<usize>,
+    primeira_linha: usize,
+}
+
+impl<'a> SourceMap<'a> {
+    pub fn new(fonte: &'a str) -> Self {
+        let inicios_de_linha = std::iter::once(0)
+            .chain(fonte.match_indices('\n').map(|(indice, _)| indice + 1))
+            .collect();
+        Self {
+            arquivo: None,
+            fonte,
+            inicios_de_linha,
+            primeira_linha: 1,
+        }
+    }
+
+    pub fn com_arquivo(mut self, arquivo: impl Into<String>) -> Self {
+        self.arquivo = Some(arquivo.into());
+        self
+    }
+
+    /// Numera as linhas a partir de `linha`, para uma fonte que é um trecho
+    /// de um arquivo maior, como uma das entradas de um arquivo lido linha a linha.
+    pub fn com_primeira_linha(mut self, linha: usize) -> Self {
+        self.primeira_linha = linha;
+        self
+    }
+
+    pub fn fonte(&self) -> &'a str {
+        self.fonte
+    }
+
+    pub fn arquivo(&self) -> Option<&str> {
+        self.arquivo.as_deref()
+    }
+
+    pub fn posicao(&self, deslocamento: usize) -> Posicao {
+        let deslocamento = deslocamento.min(self.fonte.len());
+        let indice = self
+            .inicios_de_linha
+            .partition_point(|inicio| *inicio <= deslocamento)
+            - 1;
+        let inicio = self.inicios_de_linha[indice];
+        let coluna = self.fonte[inicio..]
+            .char_indices()
+            .take_while(|(i, _)| inicio + i < deslocamento)
+            .count();
+        Posicao {
+            linha: indice + self.primeira_linha,
+            coluna: coluna + 1,
+        }
+    }
+
+    pub fn localizar(&self, span: &TextSpan) -> Localizacao {
+        Localizacao {
+            arquivo: self.arquivo.clone(),
+            posicao: self.posicao(span.start),
+        }
+    }
+
+    /// Texto da linha indicada (começando na primeira linha, em geral 1),
+    /// sem a quebra de linha.
+    pub fn linha(&self, numero: usize) -> &'a str {
+        let indice = numero.wrapping_sub(self.primeira_linha);
+        let Some(inicio) = self.inicios_de_linha.get(indice) else {
+            return "";
+        };
+        let fim = self
+            .inicios_de_linha
+            .get(indice.wrapping_add(1))
+            .map_or(self.fonte.len(), |proximo| proximo - 1);
+        self.fonte[*inicio..fim].trim_end_matches('\r')
+    }
+
+}
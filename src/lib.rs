@@ -1 +1,7 @@
 pub mod ast;
+pub mod diagnostico;
+pub mod fonte;
+pub mod numero;
+pub mod repl;
+
+pub use ast::lexer::tokenize;
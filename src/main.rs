@@ -1,47 +1,268 @@
-mod ast;
-use std::io;
-use crate::ast::parser::{Parser};
-use crate::ast::lexer::{Lexer, Token, TokenKind};
-
-fn tokenize(lexer: &mut Lexer, tokens: &mut Vec<Token>) -> bool {
-    while let Some(token) = lexer.proximo_token() {
-        if token.kind != TokenKind::EspacoEmBranco {
-            if token.kind == TokenKind::FimDeArquivo {
-                break;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use operations::repl::{ErrorKind, Reply, Session};
+
+// Arquivo no diretório do usuário onde cada linha digitada é registrada
+const ARQUIVO_DE_HISTORICO: &str = ".operations_history";
+
+// Códigos de saída; com vários erros, vale o do primeiro. Uma saída fechada
+// antes do fim, como em `operations -f grande.txt | head -1`, não é um erro:
+// a execução para ali, com o código dos erros anteriores
+const ERRO_DE_ENTRADA_OU_SAIDA: u8 = 1;
+const USO_INCORRETO: u8 = 2;
+const ERRO_LEXICO: u8 = 3;
+const ERRO_SINTATICO: u8 = 4;
+const ERRO_DE_AVALIACAO: u8 = 5;
+
+const USO: &str = "\
+uso: operations [opções] [expressão...]
+
+Avalia cada expressão dada como argumento e cada linha dos arquivos, em
+ordem e na mesma sessão, mostrando um resultado por linha; uma linha
+incompleta, como '1 +', continua na seguinte. Sem expressões nem arquivos,
+lê a entrada padrão, ou abre a sessão interativa se ela for um terminal.
+
+opções:
+  -f, --file ARQUIVO   avalia cada linha do arquivo
+  -s, --steps          mostra a avaliação passo a passo
+  -q, --quiet          não mostra os resultados, só os erros
+  -h, --help           mostra esta ajuda
+  --                   trata os argumentos seguintes como expressões,
+                       como em 'operations -- -x + 1'
+
+códigos de saída: 0 sucesso, 1 erro de leitura ou escrita, 2 uso incorreto,
+3 erro léxico, 4 erro de sintaxe ou de comando, 5 erro de avaliação";
+
+// De onde vêm as entradas, na ordem em que aparecem nos argumentos
+enum Fonte {
+    Expressao(String),
+    Arquivo(String),
+}
+
+struct Opcoes {
+    fontes: Vec<Fonte>,
+    passos: bool,
+    silencioso: bool,
+}
+
+fn main() -> ExitCode {
+    let opcoes = match ler_argumentos(env::args().skip(1)) {
+        Ok(Some(opcoes)) => opcoes,
+        Ok(None) => {
+            return match writeln!(io::stdout().lock(), "{}", USO) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(erro) => ExitCode::from(erro_de_escrita(&erro).unwrap_or(0)),
+            };
+        }
+        Err(erro) => {
+            eprintln!("erro: {}\n\n{}", erro, USO);
+            return ExitCode::from(USO_INCORRETO);
+        }
+    };
+    let mut sessao = Session::new().com_passos(opcoes.passos);
+    if opcoes.fontes.is_empty() && io::stdin().is_terminal() {
+        return interativo(&mut sessao, opcoes.silencioso);
+    }
+
+    let mut execucao = Execucao {
+        sessao,
+        silencioso: opcoes.silencioso,
+        codigo: None,
+    };
+    if opcoes.fontes.is_empty() {
+        execucao.avaliar_linhas(Some("<stdin>".to_string()), io::stdin().lock().lines());
+    }
+    for fonte in opcoes.fontes {
+        let continuar = match fonte {
+            Fonte::Expressao(expressao) => execucao.avaliar_expressao(&expressao),
+            Fonte::Arquivo(caminho) => match fs::read_to_string(&caminho) {
+                Ok(conteudo) => execucao.avaliar_linhas(Some(caminho), conteudo.lines().map(|linha| Ok(linha.to_string()))),
+                Err(erro) => {
+                    eprintln!("erro: não foi possível ler '{}': {}", caminho, erro);
+                    execucao.falhar(ERRO_DE_ENTRADA_OU_SAIDA);
+                    true
+                }
+            },
+        };
+        if !continuar {
+            break;
+        }
+    }
+    ExitCode::from(execucao.codigo.unwrap_or(0))
+}
+
+// Devolve `None` quando a ajuda foi pedida
+fn ler_argumentos(mut argumentos: impl Iterator<Item = String>) -> Result<Option<Opcoes>, String> {
+    let mut opcoes = Opcoes {
+        fontes: Vec::new(),
+        passos: false,
+        silencioso: false,
+    };
+    while let Some(argumento) = argumentos.next() {
+        match argumento.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-s" | "--steps" => opcoes.passos = true,
+            "-q" | "--quiet" => opcoes.silencioso = true,
+            "-f" | "--file" => match argumentos.next() {
+                Some(caminho) => opcoes.fontes.push(Fonte::Arquivo(caminho)),
+                None => return Err(format!("'{}' precisa do caminho de um arquivo", argumento)),
+            },
+            // Depois de `--`, tudo é expressão, mesmo o que começa por `-`
+            "--" => opcoes.fontes.extend(argumentos.by_ref().map(Fonte::Expressao)),
+            // Uma expressão como `-2 * 3` também começa por `-`; só palavras são opções
+            opcao if opcao.starts_with('-') && opcao[1..].starts_with(|c: char| c == '-' || c.is_alphabetic()) => {
+                return Err(format!("opção desconhecida '{}'", opcao))
+            }
+            _ => opcoes.fontes.push(Fonte::Expressao(argumento)),
+        }
+    }
+    Ok(Some(opcoes))
+}
+
+// Avaliação das entradas de fora do modo interativo
+struct Execucao {
+    sessao: Session,
+    silencioso: bool,
+    // O código de saída do primeiro erro
+    codigo: Option<u8>,
+}
+
+impl Execucao {
+    fn falhar(&mut self, codigo: u8) {
+        self.codigo.get_or_insert(codigo);
+    }
+
+    fn mostrar(&mut self, resposta: Reply) -> bool {
+        if let Reply::Erro(tipo, _) = &resposta {
+            self.falhar(match tipo {
+                ErrorKind::Lexico => ERRO_LEXICO,
+                ErrorKind::Sintatico | ErrorKind::Comando => ERRO_SINTATICO,
+                ErrorKind::Avaliacao => ERRO_DE_AVALIACAO,
+            });
+        }
+        match mostrar(resposta, self.silencioso) {
+            Ok(continuar) => continuar,
+            Err(erro) => {
+                if let Some(codigo) = erro_de_escrita(&erro) {
+                    self.falhar(codigo);
+                }
+                false
             }
-            if token.kind == TokenKind::Erro {
-                println!("Token inválido encontrado");
-                return false; // Indica que encontramos um token inválido
+        }
+    }
+
+    // Cada argumento é uma entrada completa, mesmo que pareça incompleta
+    fn avaliar_expressao(&mut self, expressao: &str) -> bool {
+        self.sessao.definir_arquivo(None);
+        let resposta = self.sessao.processar(expressao);
+        let continuar = self.mostrar(resposta);
+        match self.sessao.encerrar_continuacao() {
+            Some(resposta) => self.mostrar(resposta),
+            None => continuar,
+        }
+    }
+
+    fn avaliar_linhas(&mut self, arquivo: Option<String>, linhas: impl Iterator<Item = io::Result<String>>) -> bool {
+        self.sessao.definir_arquivo(arquivo);
+        for linha in linhas {
+            let linha = match linha {
+                Ok(linha) => linha,
+                Err(erro) => {
+                    eprintln!("erro: não foi possível ler a entrada: {}", erro);
+                    self.falhar(ERRO_DE_ENTRADA_OU_SAIDA);
+                    break;
+                }
+            };
+            let resposta = self.sessao.processar(&linha);
+            if !self.mostrar(resposta) {
+                return false;
             }
-            tokens.push(token);
+        }
+        match self.sessao.encerrar_continuacao() {
+            Some(resposta) => self.mostrar(resposta),
+            None => true,
         }
     }
-    true // Todos os tokens são válidos
 }
 
-fn handle_array_token() -> Vec<Token> {
-    loop {
-        println!("Digite a expressão matemática: ");
-        let mut input = String::new();
+fn interativo(sessao: &mut Session, silencioso: bool) -> ExitCode {
+    match sessao_interativa(sessao, silencioso) {
+        Ok(codigo) => codigo,
+        Err(erro) => ExitCode::from(erro_de_escrita(&erro).unwrap_or(0)),
+    }
+}
 
-        io::stdin().read_line(&mut input).expect("Erro ao ler a linha");
+// Só falha ao escrever na saída; os erros de leitura já saem com o seu código
+fn sessao_interativa(sessao: &mut Session, silencioso: bool) -> io::Result<ExitCode> {
+    // O histórico é opcional: sem diretório do usuário, a sessão segue sem ele
+    let mut historico = abrir_historico();
+    if !silencioso {
+        writeln!(io::stdout().lock(), "Digite uma expressão, ou :help para ver os comandos.")?;
+    }
 
-        let mut lexer = Lexer::new(&input);
-        let mut tokens = Vec::new();
+    let stdin = io::stdin();
+    let mut linhas = stdin.lock().lines();
+    loop {
+        let mut saida = io::stdout().lock();
+        write!(saida, "{}", if sessao.aguardando_continuacao() { "... " } else { "> " })?;
+        saida.flush()?;
+        drop(saida);
 
-        if tokenize(&mut lexer, &mut tokens) {
-            return tokens; // Retorna apenas se os tokens forem válidos
-        } else {
-            println!("Expressão inválida. Por favor, tente novamente.");
+        let Some(linha) = linhas.next() else {
+            // No fim da entrada, o que ficou pendente ainda é avaliado
+            if let Some(resposta) = sessao.encerrar_continuacao() {
+                writeln!(io::stdout().lock())?;
+                mostrar(resposta, silencioso)?;
+            }
+            writeln!(io::stdout().lock())?;
+            return Ok(ExitCode::SUCCESS);
+        };
+        let linha = match linha {
+            Ok(linha) => linha,
+            Err(erro) => {
+                writeln!(io::stdout().lock())?;
+                eprintln!("erro: não foi possível ler a entrada: {}", erro);
+                return Ok(ExitCode::from(ERRO_DE_ENTRADA_OU_SAIDA));
+            }
+        };
+        if let Some(arquivo) = historico.as_mut() {
+            if !linha.trim().is_empty() && writeln!(arquivo, "{}", linha).is_err() {
+                historico = None;
+            }
+        }
+        if !mostrar(sessao.processar(&linha), silencioso)? {
+            return Ok(ExitCode::SUCCESS);
         }
     }
 }
 
-fn main() {
-    let tokens = handle_array_token();
+// Mostra a resposta da sessão; devolve `false` se a sessão terminou
+fn mostrar(resposta: Reply, silencioso: bool) -> io::Result<bool> {
+    match resposta {
+        Reply::Saida(texto) if texto.is_empty() || silencioso => {}
+        Reply::Saida(texto) => writeln!(io::stdout().lock(), "{}", texto)?,
+        Reply::Erro(_, texto) => eprintln!("{}", texto),
+        Reply::Continuacao => {}
+        Reply::Encerrar => return Ok(false),
+    }
+    Ok(true)
+}
 
-    let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
+// O código de saída de uma falha ao escrever na saída, ou `None` se ela foi
+// fechada por quem a lia, o que encerra a execução sem erro
+fn erro_de_escrita(erro: &io::Error) -> Option<u8> {
+    if erro.kind() == io::ErrorKind::BrokenPipe {
+        return None;
+    }
+    eprintln!("erro: não foi possível escrever a saída: {}", erro);
+    Some(ERRO_DE_ENTRADA_OU_SAIDA)
+}
 
-    let _evaluated = ast.eval_step();
+fn abrir_historico() -> Option<File> {
+    let diretorio = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+    let caminho = PathBuf::from(diretorio).join(ARQUIVO_DE_HISTORICO);
+    OpenOptions::new().create(true).append(true).open(caminho).ok()
 }
@@ -0,0 +1,416 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// Inteiro de precisão arbitrária.
+///
+/// A magnitude é guardada em palavras de 32 bits, da menos para a mais
+/// significativa, sem zeros à esquerda; o zero não tem palavras e nunca é
+/// negativo. Soma, subtração e multiplicação nunca estouram; a divisão só
+/// falha quando o divisor é zero.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negativo: bool,
+    magnitude: Vec<u32>,
+}
+
+// Maior potência de 10 que cabe em uma palavra, usada para ler e escrever em base 10
+const BASE_DECIMAL: u32 = 1_000_000_000;
+const DIGITOS_POR_PALAVRA: usize = 9;
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    /// Lê uma sequência de dígitos decimais, com sinal opcional, como `-123`.
+    pub fn de_digitos(texto: &str) -> Option<Self> {
+        let (negativo, digitos) = match texto.strip_prefix('-') {
+            Some(resto) => (true, resto),
+            None => (false, texto.strip_prefix('+').unwrap_or(texto)),
+        };
+        if digitos.is_empty() || !digitos.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let mut magnitude = Vec::new();
+        // O primeiro bloco fica com o que sobra, para que os demais tenham nove dígitos
+        let primeiro = match digitos.len() % DIGITOS_POR_PALAVRA {
+            0 => DIGITOS_POR_PALAVRA,
+            resto => resto,
+        };
+        let mut inicio = 0;
+        let mut fim = primeiro;
+        while inicio < digitos.len() {
+            let bloco: u32 = digitos[inicio..fim].parse().ok()?;
+            let escala = 10u32.pow((fim - inicio) as u32);
+            multiplicar_e_somar(&mut magnitude, escala, bloco);
+            inicio = fim;
+            fim += DIGITOS_POR_PALAVRA;
+        }
+        Some(Self::com_sinal(negativo, magnitude))
+    }
+
+    pub fn eh_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn eh_negativo(&self) -> bool {
+        self.negativo
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::com_sinal(false, self.magnitude.clone())
+    }
+
+    /// Quantidade de bits da magnitude; zero para o zero.
+    pub fn bits(&self) -> u64 {
+        match self.magnitude.last() {
+            Some(topo) => self.magnitude.len() as u64 * 32 - topo.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    pub fn para_i64(&self) -> Option<i64> {
+        if self.magnitude.len() > 2 {
+            return None;
+        }
+        let magnitude = self.magnitude.iter().rev().fold(0u64, |acc, &palavra| (acc << 32) | palavra as u64);
+        if self.negativo {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    /// Valor aproximado; magnitudes além do limite de `f64` viram infinitos.
+    pub fn para_f64(&self) -> f64 {
+        let magnitude = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &palavra| acc * 4_294_967_296.0 + palavra as f64);
+        if self.negativo {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Quociente e resto da divisão truncada em direção a zero, como em `i64`:
+    /// o resto tem o sinal do dividendo. Devolve `None` se o divisor for zero.
+    pub fn checked_div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        if divisor.eh_zero() {
+            return None;
+        }
+        let (quociente, resto) = dividir_magnitudes(&self.magnitude, &divisor.magnitude);
+        Some((
+            Self::com_sinal(self.negativo != divisor.negativo, quociente),
+            Self::com_sinal(self.negativo, resto),
+        ))
+    }
+
+    pub fn pow(&self, mut expoente: u32) -> Self {
+        let mut base = self.clone();
+        let mut resultado = Self::from(1);
+        while expoente > 0 {
+            if expoente & 1 == 1 {
+                resultado = &resultado * &base;
+            }
+            expoente >>= 1;
+            if expoente > 0 {
+                base = &base * &base;
+            }
+        }
+        resultado
+    }
+
+    /// Raiz quadrada arredondada para baixo, ou `None` para números negativos.
+    pub fn raiz_quadrada(&self) -> Option<Self> {
+        if self.negativo {
+            return None;
+        }
+        if self.eh_zero() {
+            return Some(Self::zero());
+        }
+        // Método de Newton a partir de uma potência de 2 que nunca fica abaixo da raiz
+        let dois = Self::from(2);
+        let mut raiz = dois.pow(self.bits().div_ceil(2) as u32);
+        loop {
+            let (quociente, _) = self.checked_div_rem(&raiz)?;
+            let (proxima, _) = (&raiz + &quociente).checked_div_rem(&dois)?;
+            if proxima >= raiz {
+                return Some(raiz);
+            }
+            raiz = proxima;
+        }
+    }
+
+    fn com_sinal(negativo: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        Self {
+            negativo: negativo && !magnitude.is_empty(),
+            magnitude,
+        }
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(valor: i64) -> Self {
+        let magnitude = valor.unsigned_abs();
+        Self::com_sinal(valor < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.eh_zero() {
+            return write!(f, "0");
+        }
+        // Divide repetidamente por 10^9, colhendo os blocos do menos significativo
+        let mut magnitude = self.magnitude.clone();
+        let mut blocos = Vec::new();
+        while !magnitude.is_empty() {
+            blocos.push(dividir_por_palavra(&mut magnitude, BASE_DECIMAL));
+        }
+        if self.negativo {
+            write!(f, "-")?;
+        }
+        let mut blocos = blocos.iter().rev();
+        if let Some(primeiro) = blocos.next() {
+            write!(f, "{}", primeiro)?;
+        }
+        for bloco in blocos {
+            write!(f, "{:09}", bloco)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, outro: &Self) -> Option<Ordering> {
+        Some(self.cmp(outro))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, outro: &Self) -> Ordering {
+        match (self.negativo, outro.negativo) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => comparar_magnitudes(&self.magnitude, &outro.magnitude),
+            (true, true) => comparar_magnitudes(&outro.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::com_sinal(!self.negativo, self.magnitude.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, outro: &BigInt) -> BigInt {
+        if self.negativo == outro.negativo {
+            return BigInt::com_sinal(self.negativo, somar_magnitudes(&self.magnitude, &outro.magnitude));
+        }
+        // Sinais opostos: subtrai a menor magnitude da maior e fica com o sinal dela
+        match comparar_magnitudes(&self.magnitude, &outro.magnitude) {
+            Ordering::Less => BigInt::com_sinal(outro.negativo, subtrair_magnitudes(&outro.magnitude, &self.magnitude)),
+            _ => BigInt::com_sinal(self.negativo, subtrair_magnitudes(&self.magnitude, &outro.magnitude)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, outro: &BigInt) -> BigInt {
+        self + &-outro
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, outro: &BigInt) -> BigInt {
+        BigInt::com_sinal(
+            self.negativo != outro.negativo,
+            multiplicar_magnitudes(&self.magnitude, &outro.magnitude),
+        )
+    }
+}
+
+fn comparar_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn somar_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (maior, menor) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut resultado = Vec::with_capacity(maior.len() + 1);
+    let mut vai_um = 0u64;
+    for (i, &palavra) in maior.iter().enumerate() {
+        let soma = palavra as u64 + menor.get(i).copied().unwrap_or(0) as u64 + vai_um;
+        resultado.push(soma as u32);
+        vai_um = soma >> 32;
+    }
+    if vai_um > 0 {
+        resultado.push(vai_um as u32);
+    }
+    resultado
+}
+
+// Exige que `a` seja maior ou igual a `b`
+fn subtrair_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut resultado = Vec::with_capacity(a.len());
+    let mut emprestimo = 0i64;
+    for (i, &palavra) in a.iter().enumerate() {
+        let diferenca = palavra as i64 - b.get(i).copied().unwrap_or(0) as i64 - emprestimo;
+        resultado.push(diferenca as u32);
+        emprestimo = if diferenca < 0 { 1 } else { 0 };
+    }
+    resultado
+}
+
+fn multiplicar_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut resultado = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut vai_um = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let produto = x as u64 * y as u64 + resultado[i + j] as u64 + vai_um;
+            resultado[i + j] = produto as u32;
+            vai_um = produto >> 32;
+        }
+        resultado[i + b.len()] = vai_um as u32;
+    }
+    resultado
+}
+
+fn multiplicar_e_somar(magnitude: &mut Vec<u32>, fator: u32, parcela: u32) {
+    let mut vai_um = parcela as u64;
+    for palavra in magnitude.iter_mut() {
+        let produto = *palavra as u64 * fator as u64 + vai_um;
+        *palavra = produto as u32;
+        vai_um = produto >> 32;
+    }
+    if vai_um > 0 {
+        magnitude.push(vai_um as u32);
+    }
+}
+
+// Divide a magnitude no lugar e devolve o resto
+fn dividir_por_palavra(magnitude: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut resto = 0u64;
+    for palavra in magnitude.iter_mut().rev() {
+        let atual = (resto << 32) | *palavra as u64;
+        *palavra = (atual / divisor as u64) as u32;
+        resto = atual % divisor as u64;
+    }
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    resto as u32
+}
+
+// Divisão longa de Knuth (algoritmo D), com divisor não nulo
+fn dividir_magnitudes(dividendo: &[u32], divisor: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if comparar_magnitudes(dividendo, divisor) == Ordering::Less {
+        return (Vec::new(), dividendo.to_vec());
+    }
+    if divisor.len() == 1 {
+        let mut quociente = dividendo.to_vec();
+        let resto = dividir_por_palavra(&mut quociente, divisor[0]);
+        return (quociente, vec![resto]);
+    }
+
+    // Normaliza para que a palavra mais significativa do divisor tenha o bit alto ligado
+    let n = divisor.len();
+    let m = dividendo.len() - n;
+    let deslocamento = divisor[n - 1].leading_zeros();
+    let v = deslocar_a_esquerda(divisor, deslocamento, n);
+    let mut u = deslocar_a_esquerda(dividendo, deslocamento, dividendo.len() + 1);
+    let mut quociente = vec![0u32; m + 1];
+    const BASE: u64 = 1 << 32;
+
+    for j in (0..=m).rev() {
+        let numerador = ((u[j + n] as u64) << 32) | u[j + n - 1] as u64;
+        let mut q = numerador / v[n - 1] as u64;
+        let mut r = numerador % v[n - 1] as u64;
+        while q >= BASE || q * v[n - 2] as u64 > ((r << 32) | u[j + n - 2] as u64) {
+            q -= 1;
+            r += v[n - 1] as u64;
+            if r >= BASE {
+                break;
+            }
+        }
+
+        // Subtrai q * v da janela atual do dividendo
+        let mut emprestimo = 0i64;
+        for i in 0..n {
+            let produto = q * v[i] as u64;
+            let t = u[i + j] as i64 - emprestimo - (produto & 0xFFFF_FFFF) as i64;
+            u[i + j] = t as u32;
+            emprestimo = (produto >> 32) as i64 - (t >> 32);
+        }
+        let t = u[j + n] as i64 - emprestimo;
+        u[j + n] = t as u32;
+
+        // A estimativa passou do ponto em uma unidade: devolve o divisor
+        if t < 0 {
+            q -= 1;
+            let mut vai_um = 0u64;
+            for i in 0..n {
+                let soma = u[i + j] as u64 + v[i] as u64 + vai_um;
+                u[i + j] = soma as u32;
+                vai_um = soma >> 32;
+            }
+            u[j + n] = u[j + n].wrapping_add(vai_um as u32);
+        }
+        quociente[j] = q as u32;
+    }
+
+    let mut resto = deslocar_a_direita(&u[..n], deslocamento);
+    while quociente.last() == Some(&0) {
+        quociente.pop();
+    }
+    while resto.last() == Some(&0) {
+        resto.pop();
+    }
+    (quociente, resto)
+}
+
+fn deslocar_a_esquerda(palavras: &[u32], deslocamento: u32, tamanho: usize) -> Vec<u32> {
+    let mut resultado = vec![0u32; tamanho];
+    let mut vai = 0u32;
+    for (i, &palavra) in palavras.iter().enumerate() {
+        resultado[i] = (palavra << deslocamento) | vai;
+        vai = if deslocamento == 0 { 0 } else { palavra >> (32 - deslocamento) };
+    }
+    if palavras.len() < tamanho {
+        resultado[palavras.len()] = vai;
+    }
+    resultado
+}
+
+fn deslocar_a_direita(palavras: &[u32], deslocamento: u32) -> Vec<u32> {
+    let mut resultado = vec![0u32; palavras.len()];
+    for i in 0..palavras.len() {
+        let seguinte = palavras.get(i + 1).copied().unwrap_or(0);
+        resultado[i] = if deslocamento == 0 {
+            palavras[i]
+        } else {
+            (palavras[i] >> deslocamento) | (seguinte << (32 - deslocamento))
+        };
+    }
+    resultado
+}
@@ -0,0 +1,447 @@
+pub mod inteiro;
+pub mod racional;
+
+use std::fmt::{Debug, Display};
+use crate::ast::avaliador::{EvalError, Evaluator, Rounding};
+use crate::ast::lexer::TextSpan;
+use crate::numero::inteiro::BigInt;
+use crate::numero::racional::Rational;
+
+/// Falha de uma operação de [`Number`], ainda sem o trecho da entrada onde
+/// ocorreu; o avaliador a converte no [`EvalError`] correspondente.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErroAritmetico {
+    DivisaoPorZero,
+    Overflow,
+    ExpoenteNegativo,
+    ExpoenteFracionario,
+    LiteralDecimal,
+    Indefinido,
+    Irracional,
+}
+
+impl ErroAritmetico {
+    pub(crate) fn com_span(self, span: &TextSpan) -> EvalError {
+        let span = span.clone();
+        match self {
+            ErroAritmetico::DivisaoPorZero => EvalError::DivisaoPorZero { span },
+            ErroAritmetico::Overflow => EvalError::Overflow { span },
+            ErroAritmetico::ExpoenteNegativo => EvalError::ExpoenteNegativo { span },
+            ErroAritmetico::ExpoenteFracionario => EvalError::ExpoenteFracionario { span },
+            ErroAritmetico::LiteralDecimal => EvalError::LiteralDecimal { span },
+            ErroAritmetico::Indefinido => EvalError::ResultadoIndefinido { span },
+            ErroAritmetico::Irracional => EvalError::ResultadoIrracional { span },
+        }
+    }
+}
+
+pub type Resultado<N> = Result<N, ErroAritmetico>;
+
+/// Domínio numérico de uma árvore: os valores guardados em
+/// [`Node::Numero`](crate::ast::parser::Node::Numero) e produzidos pelo
+/// [`Evaluator`].
+///
+/// O parser converte cada literal com `de_inteiro`, `de_inteiro_grande` ou
+/// `de_decimal`, conforme o token; as demais operações são verificadas e
+/// devolvem um [`ErroAritmetico`] em vez de estourar ou entrar em pânico.
+/// Cada domínio decide o que `/` significa: `i64` e [`BigInt`] fazem a
+/// divisão inteira segundo a convenção de arredondamento, enquanto `f64` e
+/// [`Rational`] fazem a divisão exata. A ordem de `PartialOrd` é a usada
+/// por funções como `abs`, `min` e `max`.
+pub trait Number: Clone + Debug + Display + PartialOrd {
+    fn de_inteiro(valor: i64) -> Self;
+
+    /// Literais inteiros além do limite de `i64`.
+    fn de_inteiro_grande(_valor: &BigInt) -> Resultado<Self> {
+        Err(ErroAritmetico::Overflow)
+    }
+
+    /// Literais com parte fracionária ou expoente, como `2.5` e `1e-3`.
+    fn de_decimal(_literal: &str) -> Resultado<Self> {
+        Err(ErroAritmetico::LiteralDecimal)
+    }
+
+    fn somar(&self, outro: &Self) -> Resultado<Self>;
+    fn subtrair(&self, outro: &Self) -> Resultado<Self>;
+    fn multiplicar(&self, outro: &Self) -> Resultado<Self>;
+    fn dividir(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self>;
+    /// Quociente inteiro, arredondado segundo a convenção.
+    fn quociente(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self>;
+    fn resto(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self>;
+    fn potencia(&self, expoente: &Self) -> Resultado<Self>;
+    fn negar(&self) -> Resultado<Self>;
+
+    /// Raiz quadrada usada pela função `sqrt`; os domínios inteiros a
+    /// arredondam para baixo.
+    fn raiz_quadrada(&self) -> Resultado<Self> {
+        Err(ErroAritmetico::Indefinido)
+    }
+
+    /// Texto usado para o valor nas expressões do rastro.
+    fn exibir(&self, _avaliador: &Evaluator) -> String {
+        self.to_string()
+    }
+}
+
+impl Number for i64 {
+    fn de_inteiro(valor: i64) -> Self {
+        valor
+    }
+
+    fn somar(&self, outro: &Self) -> Resultado<Self> {
+        self.checked_add(*outro).ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn subtrair(&self, outro: &Self) -> Resultado<Self> {
+        self.checked_sub(*outro).ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn multiplicar(&self, outro: &Self) -> Resultado<Self> {
+        self.checked_mul(*outro).ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn dividir(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self> {
+        self.quociente(outro, arredondamento)
+    }
+
+    fn quociente(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self> {
+        let (esquerda, direita) = (*self, *outro);
+        if direita == 0 {
+            return Err(ErroAritmetico::DivisaoPorZero);
+        }
+        match arredondamento {
+            Rounding::Truncar => esquerda.checked_div(direita),
+            // A divisão verificada vem antes do resto, que estoura no mesmo
+            // caso (`i64::MIN` por -1) e entraria em pânico com `%`
+            Rounding::Piso => esquerda.checked_div(direita).map(|quociente| {
+                if esquerda.wrapping_rem(direita) != 0 && (esquerda < 0) != (direita < 0) {
+                    quociente - 1
+                } else {
+                    quociente
+                }
+            }),
+            Rounding::Euclides => esquerda.checked_div_euclid(direita),
+        }
+        .ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn resto(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self> {
+        let (esquerda, direita) = (*self, *outro);
+        if direita == 0 {
+            return Err(ErroAritmetico::DivisaoPorZero);
+        }
+        // O resto de `i64::MIN` por -1 é 0, embora o quociente estoure
+        let resto = esquerda.wrapping_rem(direita);
+        Ok(match arredondamento {
+            Rounding::Truncar => resto,
+            Rounding::Piso if resto != 0 && (resto < 0) != (direita < 0) => resto + direita,
+            Rounding::Piso => resto,
+            Rounding::Euclides => esquerda.wrapping_rem_euclid(direita),
+        })
+    }
+
+    fn potencia(&self, expoente: &Self) -> Resultado<Self> {
+        let (base, expoente) = (*self, *expoente);
+        if expoente < 0 {
+            return Err(ErroAritmetico::ExpoenteNegativo);
+        }
+        match u32::try_from(expoente) {
+            Ok(expoente) => base.checked_pow(expoente),
+            // Expoentes enormes só têm resultado representável para bases 0, 1 e -1
+            Err(_) => match base {
+                0 | 1 => Some(base),
+                -1 => Some(if expoente % 2 == 0 { 1 } else { -1 }),
+                _ => None,
+            },
+        }
+        .ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn negar(&self) -> Resultado<Self> {
+        self.checked_neg().ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn raiz_quadrada(&self) -> Resultado<Self> {
+        if *self < 0 {
+            return Err(ErroAritmetico::Indefinido);
+        }
+        Ok(self.isqrt())
+    }
+}
+
+// Infinitos e NaN nunca escapam do domínio real: viram erros
+fn finito(valor: f64) -> Resultado<f64> {
+    if valor.is_nan() {
+        Err(ErroAritmetico::Indefinido)
+    } else if valor.is_infinite() {
+        Err(ErroAritmetico::Overflow)
+    } else {
+        Ok(valor)
+    }
+}
+
+impl Number for f64 {
+    fn de_inteiro(valor: i64) -> Self {
+        valor as f64
+    }
+
+    fn de_inteiro_grande(valor: &BigInt) -> Resultado<Self> {
+        finito(valor.para_f64())
+    }
+
+    fn de_decimal(literal: &str) -> Resultado<Self> {
+        // Literais grandes demais, como `1e999`, viram infinito ao serem lidos
+        finito(literal.parse().map_err(|_| ErroAritmetico::Indefinido)?)
+    }
+
+    fn somar(&self, outro: &Self) -> Resultado<Self> {
+        finito(self + outro)
+    }
+
+    fn subtrair(&self, outro: &Self) -> Resultado<Self> {
+        finito(self - outro)
+    }
+
+    fn multiplicar(&self, outro: &Self) -> Resultado<Self> {
+        finito(self * outro)
+    }
+
+    fn dividir(&self, outro: &Self, _arredondamento: Rounding) -> Resultado<Self> {
+        if *outro == 0.0 {
+            return Err(ErroAritmetico::DivisaoPorZero);
+        }
+        finito(self / outro)
+    }
+
+    fn quociente(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self> {
+        if *outro == 0.0 {
+            return Err(ErroAritmetico::DivisaoPorZero);
+        }
+        finito(match arredondamento {
+            Rounding::Truncar => (self / outro).trunc(),
+            Rounding::Piso => (self / outro).floor(),
+            Rounding::Euclides => self.div_euclid(*outro),
+        })
+    }
+
+    fn resto(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self> {
+        if *outro == 0.0 {
+            return Err(ErroAritmetico::DivisaoPorZero);
+        }
+        finito(match arredondamento {
+            Rounding::Truncar => self % outro,
+            Rounding::Piso => self - outro * (self / outro).floor(),
+            Rounding::Euclides => self.rem_euclid(*outro),
+        })
+    }
+
+    fn potencia(&self, expoente: &Self) -> Resultado<Self> {
+        finito(self.powf(*expoente))
+    }
+
+    fn negar(&self) -> Resultado<Self> {
+        Ok(-self)
+    }
+
+    fn raiz_quadrada(&self) -> Resultado<Self> {
+        finito(self.sqrt())
+    }
+
+    // Fora dessa faixa a forma decimal teria dezenas de zeros; a notação
+    // científica usa o mesmo formato dos literais, como `2e300` e `2.5e-8`
+    fn exibir(&self, _avaliador: &Evaluator) -> String {
+        let modulo = self.abs();
+        if modulo != 0.0 && !(1e-6..1e16).contains(&modulo) {
+            format!("{:e}", self)
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+impl Number for Rational {
+    fn de_inteiro(valor: i64) -> Self {
+        Rational::inteiro(valor)
+    }
+
+    fn de_inteiro_grande(valor: &BigInt) -> Resultado<Self> {
+        valor.para_i64().map(Rational::inteiro).ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn de_decimal(literal: &str) -> Resultado<Self> {
+        Rational::de_decimal(literal).ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn somar(&self, outro: &Self) -> Resultado<Self> {
+        self.checked_add(outro).ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn subtrair(&self, outro: &Self) -> Resultado<Self> {
+        self.checked_sub(outro).ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn multiplicar(&self, outro: &Self) -> Resultado<Self> {
+        self.checked_mul(outro).ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn dividir(&self, outro: &Self, _arredondamento: Rounding) -> Resultado<Self> {
+        if outro.eh_zero() {
+            return Err(ErroAritmetico::DivisaoPorZero);
+        }
+        self.checked_div(outro).ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn quociente(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self> {
+        let razao = self.dividir(outro, arredondamento)?;
+        let quociente = match arredondamento {
+            Rounding::Truncar => razao.truncar(),
+            Rounding::Piso => razao.piso(),
+            // O quociente euclidiano deixa sempre um resto não negativo
+            Rounding::Euclides if outro.numerador() < 0 => {
+                let oposta = razao.negar()?;
+                oposta.piso().checked_neg().ok_or(ErroAritmetico::Overflow)?
+            }
+            Rounding::Euclides => razao.piso(),
+        };
+        Ok(Rational::inteiro(quociente))
+    }
+
+    fn resto(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self> {
+        let quociente = self.quociente(outro, arredondamento)?;
+        self.subtrair(&outro.multiplicar(&quociente)?)
+    }
+
+    fn potencia(&self, expoente: &Self) -> Resultado<Self> {
+        if !expoente.eh_inteiro() {
+            return Err(ErroAritmetico::ExpoenteFracionario);
+        }
+        if self.eh_zero() && expoente.numerador() < 0 {
+            return Err(ErroAritmetico::DivisaoPorZero);
+        }
+        self.checked_pow(expoente.numerador()).ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn negar(&self) -> Resultado<Self> {
+        self.checked_neg().ok_or(ErroAritmetico::Overflow)
+    }
+
+    // Só é exata quando numerador e denominador são quadrados perfeitos
+    fn raiz_quadrada(&self) -> Resultado<Self> {
+        if self.numerador() < 0 {
+            return Err(ErroAritmetico::Indefinido);
+        }
+        let numerador = self.numerador().isqrt();
+        let denominador = self.denominador().isqrt();
+        if numerador * numerador != self.numerador() || denominador * denominador != self.denominador() {
+            return Err(ErroAritmetico::Irracional);
+        }
+        Rational::new(numerador, denominador).ok_or(ErroAritmetico::Overflow)
+    }
+
+    fn exibir(&self, avaliador: &Evaluator) -> String {
+        self.formatar(avaliador.formato_racional())
+    }
+}
+
+// Produtos e potências maiores que isso são recusados, para que uma expressão
+// curta como `9 ^ 9 ^ 9` não consuma toda a memória
+const LIMITE_DE_BITS: u64 = 1 << 18;
+
+fn limitado(valor: BigInt) -> Resultado<BigInt> {
+    if valor.bits() > LIMITE_DE_BITS {
+        Err(ErroAritmetico::Overflow)
+    } else {
+        Ok(valor)
+    }
+}
+
+impl Number for BigInt {
+    fn de_inteiro(valor: i64) -> Self {
+        BigInt::from(valor)
+    }
+
+    fn de_inteiro_grande(valor: &BigInt) -> Resultado<Self> {
+        Ok(valor.clone())
+    }
+
+    fn somar(&self, outro: &Self) -> Resultado<Self> {
+        Ok(self + outro)
+    }
+
+    fn subtrair(&self, outro: &Self) -> Resultado<Self> {
+        Ok(self - outro)
+    }
+
+    fn multiplicar(&self, outro: &Self) -> Resultado<Self> {
+        if self.bits() + outro.bits() > LIMITE_DE_BITS + 1 {
+            return Err(ErroAritmetico::Overflow);
+        }
+        limitado(self * outro)
+    }
+
+    fn dividir(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self> {
+        self.quociente(outro, arredondamento)
+    }
+
+    fn quociente(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self> {
+        Ok(dividir_com_arredondamento(self, outro, arredondamento)?.0)
+    }
+
+    fn resto(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self> {
+        Ok(dividir_com_arredondamento(self, outro, arredondamento)?.1)
+    }
+
+    fn potencia(&self, expoente: &Self) -> Resultado<Self> {
+        if expoente.eh_negativo() {
+            return Err(ErroAritmetico::ExpoenteNegativo);
+        }
+        // Como nos demais domínios, 0 ^ 0 vale 1
+        if expoente.eh_zero() {
+            return Ok(BigInt::from(1));
+        }
+        // 0, 1 e -1 têm potências pequenas para qualquer expoente
+        if self.bits() <= 1 {
+            let par = expoente.checked_div_rem(&BigInt::from(2)).is_some_and(|(_, resto)| resto.eh_zero());
+            return Ok(if self.eh_negativo() && par { BigInt::from(1) } else { self.clone() });
+        }
+        match expoente.para_i64().and_then(|expoente| u32::try_from(expoente).ok()) {
+            Some(expoente) if self.bits().saturating_mul(expoente as u64) <= LIMITE_DE_BITS + expoente as u64 => {
+                limitado(self.pow(expoente))
+            }
+            _ => Err(ErroAritmetico::Overflow),
+        }
+    }
+
+    fn negar(&self) -> Resultado<Self> {
+        Ok(-self)
+    }
+
+    fn raiz_quadrada(&self) -> Resultado<Self> {
+        BigInt::raiz_quadrada(self).ok_or(ErroAritmetico::Indefinido)
+    }
+}
+
+// Ajusta a divisão truncada do `BigInt` para a convenção pedida
+fn dividir_com_arredondamento(
+    esquerda: &BigInt,
+    direita: &BigInt,
+    arredondamento: Rounding,
+) -> Resultado<(BigInt, BigInt)> {
+    let (quociente, resto) = esquerda.checked_div_rem(direita).ok_or(ErroAritmetico::DivisaoPorZero)?;
+    let um = BigInt::from(1);
+    let ajustar = match arredondamento {
+        Rounding::Truncar => false,
+        Rounding::Piso => !resto.eh_zero() && resto.eh_negativo() != direita.eh_negativo(),
+        Rounding::Euclides => resto.eh_negativo(),
+    };
+    if !ajustar {
+        return Ok((quociente, resto));
+    }
+    // O resto troca de sinal somando ou subtraindo o divisor, conforme o lado
+    // para o qual o quociente anda
+    if direita.eh_negativo() && arredondamento == Rounding::Euclides {
+        Ok((&quociente + &um, &resto - direita))
+    } else {
+        Ok((&quociente - &um, &resto + direita))
+    }
+}
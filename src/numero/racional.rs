@@ -0,0 +1,229 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+/// Fração exata com numerador e denominador de 64 bits.
+///
+/// O valor está sempre normalizado: o denominador é positivo e não há fator
+/// comum entre numerador e denominador, de modo que `10/4` e `5/2` são
+/// iguais campo a campo. As operações verificam overflow e devolvem `None`
+/// quando o resultado normalizado não cabe em `i64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerador: i64,
+    denominador: i64,
+}
+
+/// Como um [`Rational`] é escrito por [`Rational::formatar`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RationalFormat {
+    /// Fração simples, como `79/3`.
+    #[default]
+    Fracao,
+    /// Parte inteira seguida da fração própria, como `26 1/3`.
+    Misto,
+    /// Decimal arredondado para a quantidade de casas indicada (no máximo
+    /// 18), como `26.333`.
+    Decimal(usize),
+}
+
+impl Rational {
+    /// Cria a fração `numerador/denominador`, ou `None` se o denominador
+    /// for zero ou se a forma normalizada não couber em `i64`.
+    pub fn new(numerador: i64, denominador: i64) -> Option<Self> {
+        Self::normalizar(numerador as i128, denominador as i128)
+    }
+
+    pub fn inteiro(valor: i64) -> Self {
+        Self {
+            numerador: valor,
+            denominador: 1,
+        }
+    }
+
+    pub fn numerador(&self) -> i64 {
+        self.numerador
+    }
+
+    pub fn denominador(&self) -> i64 {
+        self.denominador
+    }
+
+    pub fn eh_inteiro(&self) -> bool {
+        self.denominador == 1
+    }
+
+    pub fn eh_zero(&self) -> bool {
+        self.numerador == 0
+    }
+
+    /// Converte um literal decimal, como `2.5` ou `1e-3`, sem perder precisão.
+    pub fn de_decimal(literal: &str) -> Option<Self> {
+        let (mantissa, expoente) = match literal.find(['e', 'E']) {
+            Some(indice) => (&literal[..indice], literal[indice + 1..].parse::<i32>().ok()?),
+            None => (literal, 0),
+        };
+        let (inteira, fracao) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let digitos = format!("{}{}", inteira, fracao);
+        let numerador = digitos.parse::<i128>().ok()?;
+        let expoente = expoente.checked_sub(i32::try_from(fracao.len()).ok()?)?;
+
+        let escala = 10i128.checked_pow(expoente.unsigned_abs())?;
+        if expoente >= 0 {
+            Self::normalizar(numerador.checked_mul(escala)?, 1)
+        } else {
+            Self::normalizar(numerador, escala)
+        }
+    }
+
+    pub fn checked_add(&self, outro: &Self) -> Option<Self> {
+        let (a, b, c, d) = self.partes(outro);
+        Self::normalizar(a * d + c * b, b * d)
+    }
+
+    pub fn checked_sub(&self, outro: &Self) -> Option<Self> {
+        let (a, b, c, d) = self.partes(outro);
+        Self::normalizar(a * d - c * b, b * d)
+    }
+
+    pub fn checked_mul(&self, outro: &Self) -> Option<Self> {
+        let (a, b, c, d) = self.partes(outro);
+        Self::normalizar(a * c, b * d)
+    }
+
+    /// Divisão exata; `None` também para divisão por zero.
+    pub fn checked_div(&self, outro: &Self) -> Option<Self> {
+        let (a, b, c, d) = self.partes(outro);
+        Self::normalizar(a * d, b * c)
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Some(Self {
+            numerador: self.numerador.checked_neg()?,
+            denominador: self.denominador,
+        })
+    }
+
+    pub fn checked_pow(&self, expoente: i64) -> Option<Self> {
+        let base = if expoente < 0 { Self::inteiro(1).checked_div(self)? } else { *self };
+        let expoente = expoente.unsigned_abs();
+        match u32::try_from(expoente) {
+            Ok(expoente) => Some(Self {
+                numerador: base.numerador.checked_pow(expoente)?,
+                denominador: base.denominador.checked_pow(expoente)?,
+            }),
+            // Expoentes enormes só têm resultado representável para 0, 1 e -1
+            Err(_) => match (base.numerador, base.denominador) {
+                (0 | 1, 1) => Some(base),
+                (-1, 1) => Some(Self::inteiro(if expoente.is_multiple_of(2) { 1 } else { -1 })),
+                _ => None,
+            },
+        }
+    }
+
+    /// Maior inteiro menor ou igual à fração.
+    pub fn piso(&self) -> i64 {
+        self.numerador.div_euclid(self.denominador)
+    }
+
+    /// Parte inteira da fração, arredondada em direção a zero.
+    pub fn truncar(&self) -> i64 {
+        self.numerador / self.denominador
+    }
+
+    pub fn para_f64(&self) -> f64 {
+        self.numerador as f64 / self.denominador as f64
+    }
+
+    pub fn formatar(&self, formato: RationalFormat) -> String {
+        match formato {
+            RationalFormat::Fracao => self.to_string(),
+            RationalFormat::Misto => {
+                let inteira = self.truncar();
+                let resto = (self.numerador % self.denominador).abs();
+                if inteira == 0 || resto == 0 {
+                    self.to_string()
+                } else {
+                    format!("{} {}/{}", inteira, resto, self.denominador)
+                }
+            }
+            RationalFormat::Decimal(casas) => self.formatar_decimal(casas.min(18)),
+        }
+    }
+
+    // Arredonda a metade para longe do zero
+    fn formatar_decimal(&self, casas: usize) -> String {
+        let escala = 10i128.pow(casas as u32);
+        let numerador = self.numerador as i128 * escala;
+        let denominador = self.denominador as i128;
+        let mut escalado = numerador / denominador;
+        if (numerador % denominador).abs() * 2 >= denominador {
+            escalado += numerador.signum();
+        }
+
+        let sinal = if escalado < 0 { "-" } else { "" };
+        let escalado = escalado.unsigned_abs();
+        let inteira = escalado / escala as u128;
+        if casas == 0 {
+            return format!("{}{}", sinal, inteira);
+        }
+        let fracao = escalado % escala as u128;
+        format!("{}{}.{:0casas$}", sinal, inteira, fracao, casas = casas)
+    }
+
+    fn partes(&self, outro: &Self) -> (i128, i128, i128, i128) {
+        (
+            self.numerador as i128,
+            self.denominador as i128,
+            outro.numerador as i128,
+            outro.denominador as i128,
+        )
+    }
+
+    fn normalizar(numerador: i128, denominador: i128) -> Option<Self> {
+        if denominador == 0 {
+            return None;
+        }
+        let divisor = mdc(numerador.unsigned_abs(), denominador.unsigned_abs()) as i128;
+        let sinal = denominador.signum();
+        Some(Self {
+            numerador: i64::try_from(numerador / divisor * sinal).ok()?,
+            denominador: i64::try_from(denominador / divisor * sinal).ok()?,
+        })
+    }
+}
+
+fn mdc(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.denominador == 1 {
+            write!(f, "{}", self.numerador)
+        } else {
+            write!(f, "{}/{}", self.numerador, self.denominador)
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, outro: &Self) -> Option<Ordering> {
+        Some(self.cmp(outro))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, outro: &Self) -> Ordering {
+        let (a, b, c, d) = self.partes(outro);
+        (a * d).cmp(&(c * b))
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(valor: i64) -> Self {
+        Self::inteiro(valor)
+    }
+}
@@ -0,0 +1,325 @@
+use std::fmt::Write;
+use crate::ast::ambiente::Environment;
+use crate::ast::avaliador::Evaluator;
+use crate::ast::lexer::{Lexer, Token, TokenKind};
+use crate::ast::parser::{Ast, ParseError, Parser, Statement};
+use crate::diagnostico::Diagnostic;
+use crate::fonte::SourceMap;
+
+/// Nomes pelos quais a entrada seguinte se refere ao último resultado.
+pub const ULTIMO_RESULTADO: [&str; 2] = ["ans", "_"];
+
+const COMANDOS: [&str; 6] = ["help", "quit", "cancel", "steps", "ast", "tokens"];
+
+const AJUDA: &str = "\
+Digite uma expressão, como '1 + 2 * 3', uma atribuição, como 'x = 10', ou
+uma definição, como 'f(x) = x ^ 2'. Variáveis e funções valem até o fim da
+sessão, e 'ans' ou '_' guardam o último resultado. Uma entrada incompleta,
+com parênteses abertos, um operador no fim ou uma condicional sem o último
+ramo, continua na linha seguinte, até que ':cancel' a descarte.
+
+Comandos:
+  :help               mostra esta ajuda
+  :quit               encerra a sessão
+  :cancel             descarta a entrada que aguarda continuação
+  :steps on|off       mostra ou esconde a avaliação passo a passo
+  :ast [entrada]      mostra a árvore da entrada, ou da última avaliada
+  :tokens [entrada]   mostra os tokens da entrada, ou da última avaliada";
+
+/// A etapa em que uma entrada falhou.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A entrada tem caracteres que não formam nenhum token.
+    Lexico,
+    Sintatico,
+    Avaliacao,
+    /// Um comando desconhecido ou com argumentos inválidos.
+    Comando,
+}
+
+/// O que fazer após uma linha lida pela [`Session`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reply {
+    /// Texto a mostrar, possivelmente vazio, como após uma definição.
+    Saida(String),
+    /// Um erro já renderizado, com a entrada sublinhada; numa avaliação
+    /// passo a passo, precedido dos passos resolvidos antes dele.
+    Erro(ErrorKind, String),
+    /// A entrada continua na próxima linha.
+    Continuacao,
+    Encerrar,
+}
+
+/// Uma sessão interativa: recebe as linhas digitadas uma a uma e guarda as
+/// variáveis e funções definidas entre elas.
+///
+/// Linhas que começam por `:` são comandos, como `:help`; as demais são
+/// programas avaliados no ambiente da sessão. Um programa que falha não
+/// altera o ambiente. Enquanto a entrada estiver incompleta, com mais
+/// parênteses abertos que fechados, terminando em um operador ou com uma
+/// condicional sem o último ramo, as linhas são acumuladas e avaliadas
+/// juntas, como o [`Parser`] as leria num só texto; os comandos
+/// continuam valendo nesse meio tempo, e `:cancel` descarta o que foi
+/// acumulado. Para que uma linha como `: 2` ainda possa continuar um `?`, só
+/// os nomes de comando conhecidos, colados ao `:`, interrompem a entrada.
+///
+/// Os erros apontam a linha e a coluna dentro da entrada; com
+/// [`Session::definir_arquivo`], apontam o arquivo e a linha dentro dele.
+///
+/// ```
+/// use operations::repl::{Reply, Session};
+///
+/// let mut sessao = Session::new();
+/// assert_eq!(sessao.processar("x = 6"), Reply::Saida("6".to_string()));
+/// assert_eq!(sessao.processar("max(x,"), Reply::Continuacao);
+/// assert_eq!(sessao.processar("  7) * 2"), Reply::Saida("14".to_string()));
+/// assert_eq!(sessao.processar("ans + 1"), Reply::Saida("15".to_string()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    ambiente: Environment,
+    avaliador: Evaluator,
+    passos: bool,
+    // Linhas de uma entrada ainda incompleta
+    pendente: Option<String>,
+    ultima_entrada: Option<String>,
+    arquivo: Option<String>,
+    // Linhas recebidas desde o início do arquivo, e a linha em que começa a entrada atual
+    linhas: usize,
+    inicio: usize,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Liga ou desliga a avaliação passo a passo, como `:steps on|off`.
+    pub fn com_passos(mut self, passos: bool) -> Self {
+        self.passos = passos;
+        self
+    }
+
+    /// Indica de onde vêm as próximas linhas, que passam a ser numeradas a
+    /// partir de 1 nos erros; `None` volta a tratar cada entrada por si só.
+    pub fn definir_arquivo(&mut self, arquivo: Option<String>) {
+        self.arquivo = arquivo;
+        self.linhas = 0;
+    }
+
+    pub fn ambiente(&self) -> &Environment {
+        &self.ambiente
+    }
+
+    /// Se a avaliação passo a passo está ligada, como por `:steps on`.
+    pub fn passos(&self) -> bool {
+        self.passos
+    }
+
+    /// Se a última linha deixou a entrada incompleta.
+    pub fn aguardando_continuacao(&self) -> bool {
+        self.pendente.is_some()
+    }
+
+    pub fn processar(&mut self, linha: &str) -> Reply {
+        self.linhas += 1;
+        if self.pendente.is_none() {
+            self.inicio = self.linhas;
+        }
+        if let Some(comando) = comando_da_linha(linha, self.pendente.is_some()) {
+            return self.comando(comando);
+        }
+        let entrada = match self.pendente.take() {
+            Some(anterior) => format!("{}\n{}", anterior, linha),
+            None => linha.to_string(),
+        };
+        if incompleta(&entrada) {
+            self.pendente = Some(entrada);
+            return Reply::Continuacao;
+        }
+        self.avaliar(&entrada)
+    }
+
+    /// Avalia a entrada acumulada mesmo que incompleta, como no fim
+    /// da entrada padrão; devolve `None` se não havia nada pendente.
+    pub fn encerrar_continuacao(&mut self) -> Option<Reply> {
+        let entrada = self.pendente.take()?;
+        Some(self.avaliar(&entrada))
+    }
+
+    fn avaliar(&mut self, entrada: &str) -> Reply {
+        if entrada.trim().is_empty() {
+            return Reply::Saida(String::new());
+        }
+        self.ultima_entrada = Some(entrada.to_string());
+        let ast = match self.analisar(entrada) {
+            Ok(ast) => ast,
+            Err(erro) => return erro,
+        };
+
+        let mut saida = String::new();
+        // A última expressão do rastro, que dispensa repetir o resultado quando já é ele
+        let mut ultima_expressao = None;
+        // O programa roda sobre uma cópia, para que uma falha não deixe
+        // atribuições pela metade no ambiente da sessão
+        let mut ambiente = self.ambiente.clone();
+        let resultado = if self.passos {
+            let rastro = self.avaliador.rastrear_executando(&ast, &mut ambiente);
+            writeln!(saida, "{}", rastro.expressao).unwrap();
+            for passo in &rastro.passos {
+                writeln!(saida, "= {}", passo.expressao).unwrap();
+            }
+            ultima_expressao = Some(rastro.passos.last().map_or(rastro.expressao, |passo| passo.expressao.clone()));
+            rastro.resultado
+        } else {
+            self.avaliador.executar(&ast, &mut ambiente)
+        };
+        let valor = match resultado {
+            Ok(valor) => valor,
+            Err(erro) => {
+                saida.push_str(&self.renderizar(&Diagnostic::de_avaliacao(&erro, &ambiente), entrada));
+                return Reply::Erro(ErrorKind::Avaliacao, saida);
+            }
+        };
+        self.ambiente = ambiente;
+        // Um programa só de definições não tem resultado
+        if ast.instrucoes().iter().all(|instrucao| matches!(instrucao, Statement::Definicao { .. })) {
+            return Reply::Saida(saida.trim_end().to_string());
+        }
+        let texto = valor.exibir(&self.avaliador);
+        match ultima_expressao {
+            Some(expressao) if expressao == texto => {}
+            Some(_) => write!(saida, "= {}", texto).unwrap(),
+            None => saida.push_str(&texto),
+        }
+        for nome in ULTIMO_RESULTADO {
+            self.ambiente.definir(nome, valor.clone());
+        }
+        Reply::Saida(saida.trim_end().to_string())
+    }
+
+    fn comando(&mut self, comando: &str) -> Reply {
+        let (nome, argumento) = match comando.split_once(char::is_whitespace) {
+            Some((nome, argumento)) => (nome, argumento.trim()),
+            None => (comando, ""),
+        };
+        match (nome, argumento) {
+            ("help", _) => Reply::Saida(AJUDA.to_string()),
+            ("quit", _) => Reply::Encerrar,
+            ("cancel", _) => match self.pendente.take() {
+                Some(_) => Reply::Saida("entrada pendente descartada".to_string()),
+                None => Reply::Saida(String::new()),
+            },
+            ("steps", "on") => {
+                self.passos = true;
+                Reply::Saida("passo a passo ligado".to_string())
+            }
+            ("steps", "off") => {
+                self.passos = false;
+                Reply::Saida("passo a passo desligado".to_string())
+            }
+            ("steps", _) => Reply::Erro(ErrorKind::Comando, "uso: :steps on|off".to_string()),
+            ("ast" | "tokens", _) => {
+                let entrada = match (argumento, &self.ultima_entrada) {
+                    ("", Some(ultima)) => ultima.clone(),
+                    ("", None) => return Reply::Erro(ErrorKind::Comando, format!("nenhuma entrada para ':{}'", nome)),
+                    (argumento, _) => argumento.to_string(),
+                };
+                if nome == "ast" {
+                    self.mostrar_arvore(&entrada)
+                } else {
+                    Reply::Saida(mostrar_tokens(&entrada))
+                }
+            }
+            _ => Reply::Erro(
+                ErrorKind::Comando,
+                format!("comando desconhecido ':{}'; digite :help para ver os comandos", nome),
+            ),
+        }
+    }
+
+    fn renderizar(&self, diagnostico: &Diagnostic, entrada: &str) -> String {
+        match &self.arquivo {
+            Some(arquivo) => {
+                diagnostico.renderizar_com(&SourceMap::new(entrada).com_arquivo(arquivo).com_primeira_linha(self.inicio))
+            }
+            None => diagnostico.renderizar(entrada),
+        }
+    }
+
+    // Analisa a entrada, ou devolve todos os erros de sintaxe já renderizados;
+    // um caractere inválido torna o erro léxico, mesmo junto de outros erros
+    fn analisar(&self, entrada: &str) -> Result<Ast, Reply> {
+        let tokens = tokens(entrada);
+        let tipo = if tokens.iter().any(|token| token.kind == TokenKind::Erro) {
+            ErrorKind::Lexico
+        } else {
+            ErrorKind::Sintatico
+        };
+        Parser::new(tokens).parse_com_recuperacao().map_err(|erros| {
+            let texto = erros
+                .iter()
+                .map(|erro| self.renderizar(&Diagnostic::from(erro), entrada))
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            Reply::Erro(tipo, texto)
+        })
+    }
+
+    fn mostrar_arvore(&self, entrada: &str) -> Reply {
+        match self.analisar(entrada) {
+            Ok(ast) => Reply::Saida(
+                ast.instrucoes()
+                    .iter()
+                    .map(Statement::para_string)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            Err(erro) => erro,
+        }
+    }
+}
+
+// Os tokens da entrada, sem os espaços; tokens inválidos são mantidos para
+// que o parser os reporte junto com os demais erros
+fn tokens(entrada: &str) -> Vec<Token> {
+    Lexer::new(entrada)
+        .filter(|token| token.kind != TokenKind::EspacoEmBranco)
+        .collect()
+}
+
+// O comando da linha, se ela tiver um; com uma entrada pendente, só conta
+// um nome de comando conhecido
+fn comando_da_linha(linha: &str, pendente: bool) -> Option<&str> {
+    let comando = linha.trim().strip_prefix(':')?;
+    let nome = comando.split(char::is_whitespace).next().unwrap_or("");
+    (!pendente || COMANDOS.contains(&nome)).then_some(comando)
+}
+
+// Se a entrada termina antes do que falta para completá-la: parênteses
+// abertos, ou um primeiro erro de sintaxe apontando o fim, como em "1 +" ou
+// "se x > 0 entao 1". Uma entrada vazia também acusa o fim, mas não continua
+fn incompleta(entrada: &str) -> bool {
+    let tokens = tokens(entrada);
+    let saldo = tokens.iter().fold(0i64, |saldo, token| match token.kind {
+        TokenKind::ParentesesEsquerdo => saldo + 1,
+        TokenKind::ParentesesDireito => saldo - 1,
+        _ => saldo,
+    });
+    if saldo > 0 {
+        return true;
+    }
+    let resultado: Result<Ast, ParseError> = Parser::new(tokens).parse();
+    match resultado {
+        Err(erro) => !entrada.trim().is_empty() && erro.token().kind == TokenKind::FimDeArquivo,
+        Ok(_) => false,
+    }
+}
+
+fn mostrar_tokens(entrada: &str) -> String {
+    tokens(entrada)
+        .iter()
+        .map(|token| format!("{:?} {}..{} '{}'", token.kind, token.span.start, token.span.end, token.span.literal))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,261 @@
+mod common;
+
+use common::{parse, parse_erro};
+use operations::ast::ambiente::Environment;
+use operations::ast::avaliador::EvalError;
+use operations::ast::lexer::{Lexer, TokenKind};
+use operations::ast::parser::{Node, ParseError, Parser};
+use operations::ast::valor::{Value, ValueType};
+use operations::diagnostico::Diagnostic;
+use operations::tokenize;
+
+fn avaliar(expressao: &str) -> Result<Value, EvalError> {
+    parse(expressao).avaliar()
+}
+
+#[test]
+fn test_tokens_de_comparacao_e_logicos() {
+    let tipos: Vec<TokenKind> = Lexer::new("a == b != c < d <= e > f >= g && !h || verdadeiro falso")
+        .filter(|token| token.kind != TokenKind::EspacoEmBranco)
+        .map(|token| token.kind)
+        .collect();
+    assert_eq!(
+        tipos,
+        [
+            TokenKind::Identificador("a".to_string()),
+            TokenKind::IgualIgual,
+            TokenKind::Identificador("b".to_string()),
+            TokenKind::Diferente,
+            TokenKind::Identificador("c".to_string()),
+            TokenKind::Menor,
+            TokenKind::Identificador("d".to_string()),
+            TokenKind::MenorOuIgual,
+            TokenKind::Identificador("e".to_string()),
+            TokenKind::Maior,
+            TokenKind::Identificador("f".to_string()),
+            TokenKind::MaiorOuIgual,
+            TokenKind::Identificador("g".to_string()),
+            TokenKind::E,
+            TokenKind::Nao,
+            TokenKind::Identificador("h".to_string()),
+            TokenKind::Ou,
+            TokenKind::Booleano(true),
+            TokenKind::Booleano(false),
+            TokenKind::FimDeArquivo,
+        ]
+    );
+    // `&` e `|` sozinhos não são operadores
+    assert!(tokenize("a & b").is_err());
+    assert!(tokenize("a | b").is_err());
+}
+
+#[test]
+fn test_comparacoes() {
+    assert_eq!(avaliar("1 + 2 == 3"), Ok(Value::Booleano(true)));
+    assert_eq!(avaliar("2 * 3 != 6"), Ok(Value::Booleano(false)));
+    assert_eq!(avaliar("-1 < 0"), Ok(Value::Booleano(true)));
+    assert_eq!(avaliar("4 <= 2 ^ 2"), Ok(Value::Booleano(true)));
+    assert_eq!(avaliar("7 // 2 > 3"), Ok(Value::Booleano(false)));
+    assert_eq!(avaliar("max(1, 5) >= 5"), Ok(Value::Booleano(true)));
+    // Booleanos também podem ser comparados entre si
+    assert_eq!(avaliar("(1 < 2) == verdadeiro"), Ok(Value::Booleano(true)));
+    assert_eq!(avaliar("falso != falso"), Ok(Value::Booleano(false)));
+}
+
+#[test]
+fn test_precedencia_dos_operadores_logicos() {
+    let ambiente = Environment::new().com("idade", 20).com("saldo", 0);
+    let ast = parse("idade >= 18 && saldo > 0 || idade == 20");
+    assert_eq!(ast.raiz().unwrap().para_string(), "(((idade >= 18) && (saldo > 0)) || (idade == 20))");
+    assert_eq!(ast.avaliar_em(&ambiente), Ok(Value::Booleano(true)));
+    // `&&` liga mais forte que `||`, e `!` mais forte que ambos
+    assert_eq!(avaliar("verdadeiro || falso && falso"), Ok(Value::Booleano(true)));
+    assert_eq!(avaliar("!falso && falso"), Ok(Value::Booleano(false)));
+    assert_eq!(avaliar("!(1 > 2)"), Ok(Value::Booleano(true)));
+    assert_eq!(parse("!a == b").raiz().unwrap().para_string(), "(!a == b)");
+}
+
+#[test]
+fn test_curto_circuito() {
+    // O lado direito não é avaliado quando o esquerdo já decide o resultado
+    assert_eq!(avaliar("falso && 1 / 0 == 1"), Ok(Value::Booleano(false)));
+    assert_eq!(avaliar("verdadeiro || desconhecida"), Ok(Value::Booleano(true)));
+    assert!(matches!(avaliar("verdadeiro && 1 / 0 == 1"), Err(EvalError::DivisaoPorZero { .. })));
+}
+
+#[test]
+fn test_variaveis_booleanas() {
+    let mut ambiente = Environment::new().com("ativo", Value::Booleano(true));
+    assert_eq!(parse("maior = 3 > 2; ativo && maior").executar(&mut ambiente), Ok(Value::Booleano(true)));
+    assert_eq!(ambiente.obter("maior"), Some(&Value::Booleano(true)));
+    assert_eq!(ambiente.obter("ativo").unwrap().tipo(), ValueType::Booleano);
+    assert_eq!(parse("f(x) = x > 0; f(2) && !f(-2)").avaliar(), Ok(Value::Booleano(true)));
+}
+
+#[test]
+fn test_erros_de_tipo() {
+    let entrada = "1 + (2 > 1)";
+    let erro = avaliar(entrada).unwrap_err();
+    assert_eq!(
+        erro,
+        EvalError::TipoIncorreto {
+            esperado: ValueType::Numero,
+            encontrado: ValueType::Booleano,
+            span: erro.span().clone(),
+        }
+    );
+    // O span aponta para o operando com o tipo errado, com os parênteses
+    assert_eq!((erro.span().start, erro.span().end), (4, 11));
+    let texto = Diagnostic::from(&erro).renderizar(entrada);
+    assert!(texto.starts_with("erro: tipo incorreto: esperava número, encontrou booleano\n"));
+
+    let erro = avaliar("1 && verdadeiro").unwrap_err();
+    assert!(matches!(erro, EvalError::TipoIncorreto { esperado: ValueType::Booleano, encontrado: ValueType::Numero, .. }));
+    assert_eq!((erro.span().start, erro.span().end), (0, 1));
+    let texto = Diagnostic::from(&erro).renderizar("1 && verdadeiro");
+    assert!(texto.ends_with("= dica: '&&', '||' e '!' só aceitam booleanos, como o resultado de uma comparação"));
+
+    assert!(matches!(avaliar("!3"), Err(EvalError::TipoIncorreto { esperado: ValueType::Booleano, .. })));
+    assert!(matches!(avaliar("-verdadeiro"), Err(EvalError::TipoIncorreto { esperado: ValueType::Numero, .. })));
+    assert!(matches!(avaliar("1 == falso"), Err(EvalError::TipoIncorreto { .. })));
+    assert!(matches!(avaliar("abs(1 < 2)"), Err(EvalError::TipoIncorreto { .. })));
+    // Comparações não se encadeiam: `1 < 2 < 3` compara um booleano com um número
+    assert!(matches!(avaliar("1 < 2 < 3"), Err(EvalError::TipoIncorreto { .. })));
+}
+
+#[test]
+fn test_rastro_com_booleanos() {
+    let rastro = parse("2 > 1 && 3 < 1 + 1").eval_step();
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(
+        expressoes,
+        ["(verdadeiro && (3 < (1 + 1)))", "(verdadeiro && (3 < 2))", "(verdadeiro && falso)", "falso"]
+    );
+    assert_eq!(rastro.passos[0].operandos, [2, 1]);
+    assert_eq!(rastro.resultado, Ok(Value::Booleano(false)));
+
+    // Com curto-circuito, o lado direito desaparece em um único passo
+    let rastro = parse("1 > 2 && 1 / 0 == 1").eval_step();
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(expressoes, ["(falso && ((1 / 0) == 1))", "falso"]);
+    assert_eq!(rastro.resultado, Ok(Value::Booleano(false)));
+}
+
+fn preco(regra: &str, x: i64) -> Result<Value, EvalError> {
+    parse(regra).avaliar_em(&Environment::new().com("x", x))
+}
+
+#[test]
+fn test_as_duas_formas_da_condicional() {
+    for regra in ["se x > 100 entao x * 9 / 10 senao x", "x > 100 ? x*9/10 : x", "se x > 100 então x * 9 / 10 senão x"] {
+        assert_eq!(preco(regra, 200), Ok(Value::Numero(180)));
+        assert_eq!(preco(regra, 50), Ok(Value::Numero(50)));
+    }
+    let ast = parse("x > 100 ? x*9/10 : x");
+    assert_eq!(ast.raiz().unwrap().para_string(), "(se (x > 100) entao ((x * 9) / 10) senao x)");
+    match ast.raiz().unwrap() {
+        Node::Condicional { condicao, span, .. } => {
+            assert_eq!(condicao.para_string(), "(x > 100)");
+            assert_eq!((span.start, span.end), (0, 20));
+        }
+        outro => panic!("esperava uma condicional, obteve {:?}", outro),
+    }
+}
+
+#[test]
+fn test_agrupamento_da_condicional() {
+    // O último ramo se estende até o fim da expressão
+    assert_eq!(parse("se a entao 1 senao 2 + 3").raiz().unwrap().para_string(), "(se a entao 1 senao (2 + 3))");
+    assert_eq!(parse("1 + (a ? 2 : 3) * 4").raiz().unwrap().para_string(), "(1 + ((se a entao 2 senao 3) * 4))");
+    // A condição do `?` é tudo o que vem antes
+    assert_eq!(parse("x + 1 > 2 || y ? 1 : 0").raiz().unwrap().para_string(), "(se (((x + 1) > 2) || y) entao 1 senao 0)");
+    // Condicionais aninhadas agrupam à direita
+    assert_eq!(
+        parse("a ? 1 : b ? 2 : 3").raiz().unwrap().para_string(),
+        "(se a entao 1 senao (se b entao 2 senao 3))"
+    );
+    assert_eq!(
+        parse("se a entao se b entao 1 senao 2 senao 3").raiz().unwrap().para_string(),
+        "(se a entao (se b entao 1 senao 2) senao 3)"
+    );
+    assert_eq!(parse("a ? b ? 1 : 2 : 3").raiz().unwrap().para_string(), "(se a entao (se b entao 1 senao 2) senao 3)");
+    // Dentro de chamadas e em várias linhas
+    assert_eq!(parse("max(x > 0 ? x : 0, 1)").raiz().unwrap().para_string(), "max((se (x > 0) entao x senao 0), 1)");
+    assert_eq!(parse("se x > 0\n  entao 1\n  senao 2\n").instrucoes().len(), 1);
+}
+
+#[test]
+fn test_ramo_nao_escolhido_nao_e_avaliado() {
+    assert_eq!(parse("se verdadeiro entao 1 senao 1 / 0").avaliar(), Ok(Value::Numero(1)));
+    assert_eq!(parse("0 == 0 ? desconhecida : 2").avaliar_em(&Environment::new().com("desconhecida", 7)), Ok(Value::Numero(7)));
+    let erro = parse("1 > 2 ? 1 / 0 : sqrt(-1) == 0 ? 1 : 2").avaliar().unwrap_err();
+    assert!(matches!(erro, EvalError::ResultadoIndefinido { .. }));
+    assert_eq!(erro.span().start, 16);
+    assert!(matches!(parse("se falso entao 1 senao 1 / 0").avaliar(), Err(EvalError::DivisaoPorZero { .. })));
+    // Os ramos podem ter tipos diferentes
+    assert_eq!(parse("x = 3; x > 2 ? x > 5 : 0").avaliar(), Ok(Value::Booleano(false)));
+}
+
+#[test]
+fn test_recursao_com_caso_base() {
+    let programa = "fat(n) = se n <= 1 entao 1 senao n * fat(n - 1); fat(10)";
+    assert_eq!(parse(programa).avaliar(), Ok(Value::Numero(3628800)));
+    let fib = "fib(n) = n < 2 ? n : fib(n - 1) + fib(n - 2); fib(15)";
+    assert_eq!(parse(fib).avaliar(), Ok(Value::Numero(610)));
+}
+
+#[test]
+fn test_condicao_precisa_ser_booleana() {
+    let erro = parse("se 1 + 1 entao 2 senao 3").avaliar().unwrap_err();
+    assert!(matches!(erro, EvalError::TipoIncorreto { esperado: ValueType::Booleano, encontrado: ValueType::Numero, .. }));
+    assert_eq!((erro.span().start, erro.span().end), (3, 8));
+}
+
+#[test]
+fn test_condicional_incompleta() {
+    let entrada = "se x > 0 entao x";
+    let erro = parse_erro(entrada);
+    assert!(matches!(&erro, ParseError::CondicionalIncompleta(t, TokenKind::Senao) if t.kind == TokenKind::FimDeArquivo));
+    let texto = Diagnostic::from(&erro).renderizar(entrada);
+    assert!(texto.starts_with("erro: condicional incompleta: falta 'senao' no fim da expressão\n"));
+    assert!(texto.ends_with(
+        "= dica: uma condicional tem a forma 'se condição entao valor senao valor' ou 'condição ? valor : valor'"
+    ));
+
+    let erro = parse_erro("a ? 1; 2");
+    assert!(matches!(&erro, ParseError::CondicionalIncompleta(t, TokenKind::DoisPontos) if t.span.literal == ";"));
+    assert_eq!(erro.to_string(), "condicional incompleta: falta ':' antes de ';'");
+    assert!(matches!(parse_erro("se x 1 entao 2 senao 3"), ParseError::TokenInesperado(t) if t.span.literal == "1"));
+    assert!(matches!(parse_erro("x senao 1"), ParseError::TokenInesperado(t) if t.kind == TokenKind::Senao));
+    assert!(matches!(parse_erro("1 : 2"), ParseError::TokenInesperado(t) if t.kind == TokenKind::DoisPontos));
+    assert!(matches!(parse_erro("? 1 : 2"), ParseError::OperandoAusente(t) if t.kind == TokenKind::Interrogacao));
+
+    // Com recuperação, o resto da entrada continua sendo analisado
+    let erros = Parser::new(tokenize("se x entao 1; 2 +").unwrap()).parse_com_recuperacao().unwrap_err();
+    let mensagens: Vec<String> = erros.iter().map(ToString::to_string).collect();
+    assert_eq!(mensagens, ["condicional incompleta: falta 'senao' antes de ';'", "operando ausente no fim da expressão"]);
+}
+
+#[test]
+fn test_rastro_mostra_o_ramo_escolhido() {
+    let rastro = parse("x = 150; se x > 100 entao x * 9 / 10 senao x / 0").eval_step();
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(
+        expressoes,
+        [
+            "x = 150; (se verdadeiro entao ((x * 9) / 10) senao (x / 0))",
+            "x = 150; (se verdadeiro entao (1350 / 10) senao (x / 0))",
+            "x = 150; (se verdadeiro entao 135 senao (x / 0))",
+            "x = 150; 135",
+        ]
+    );
+    // O passo da condicional guarda a condição e o valor do ramo escolhido
+    let ultimo = rastro.passos.last().unwrap();
+    assert!(matches!(ultimo.no, Node::Condicional { .. }));
+    assert_eq!(ultimo.operandos, [Value::Booleano(true), Value::Numero(135)]);
+    assert_eq!(rastro.resultado, Ok(Value::Numero(135)));
+
+    let rastro = parse("1 > 2 ? 1 / 0 : 7").eval_step();
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(expressoes, ["(se falso entao (1 / 0) senao 7)", "7"]);
+}
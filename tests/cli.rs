@@ -0,0 +1,109 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn executar(argumentos: &[&str], entrada: &str) -> Output {
+    let mut processo = Command::new(env!("CARGO_BIN_EXE_operations"))
+        .args(argumentos)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    processo.stdin.take().unwrap().write_all(entrada.as_bytes()).unwrap();
+    processo.wait_with_output().unwrap()
+}
+
+fn saida(resultado: &Output) -> String {
+    String::from_utf8_lossy(&resultado.stdout).into_owned()
+}
+
+fn erros(resultado: &Output) -> String {
+    String::from_utf8_lossy(&resultado.stderr).into_owned()
+}
+
+#[test]
+fn test_expressoes_nos_argumentos() {
+    let resultado = executar(&["1 + 2*3"], "");
+    assert_eq!(saida(&resultado), "7\n");
+    assert_eq!(resultado.status.code(), Some(0));
+    // Os argumentos compartilham a sessão; `-2` é uma expressão, não uma opção
+    let resultado = executar(&["x = 2", "x * 3", "-2 ^ 2", "--", "-x"], "");
+    assert_eq!(saida(&resultado), "2\n6\n-4\n-2\n");
+}
+
+#[test]
+fn test_entrada_padrao_e_arquivos() {
+    let resultado = executar(&[], "2*3\n\nx = 4\nmax(x,\n  1)\n");
+    assert_eq!(saida(&resultado), "6\n4\n4\n");
+    assert!(erros(&resultado).is_empty());
+
+    let arquivo = std::env::temp_dir().join(format!("operations-cli-{}.txt", std::process::id()));
+    std::fs::write(&arquivo, "f(n) = n ^ 2\nf(3)\nf(y)\n").unwrap();
+    let caminho = arquivo.to_str().unwrap();
+    let resultado = executar(&["-f", caminho, "f(4)"], "");
+    std::fs::remove_file(&arquivo).unwrap();
+    assert_eq!(saida(&resultado), "9\n16\n");
+    assert!(erros(&resultado).contains(&format!(" --> {}:3:3\n", caminho)));
+    assert_eq!(resultado.status.code(), Some(5));
+
+    // Uma instrução que termina num operador continua na linha seguinte
+    std::fs::write(&arquivo, "1 +\n2\nse 1 > 0\n  entao 10\n  senao 20\n").unwrap();
+    let resultado = executar(&["-f", caminho], "");
+    std::fs::remove_file(&arquivo).unwrap();
+    assert_eq!(saida(&resultado), "3\n10\n");
+    assert_eq!(resultado.status.code(), Some(0));
+}
+
+#[test]
+fn test_passos_e_modo_silencioso() {
+    let resultado = executar(&["--steps", "1 + 2 * 3"], "");
+    assert_eq!(saida(&resultado), "(1 + (2 * 3))\n= (1 + 6)\n= 7\n");
+    let resultado = executar(&["-q", "1 + 2", "1 / 0"], "");
+    assert!(saida(&resultado).is_empty());
+    assert!(erros(&resultado).starts_with("erro: divisão por zero\n"));
+    assert_eq!(resultado.status.code(), Some(5));
+}
+
+#[test]
+fn test_codigos_de_saida() {
+    assert_eq!(executar(&["1 # 2"], "").status.code(), Some(3));
+    assert_eq!(executar(&["(1 +"], "").status.code(), Some(4));
+    assert_eq!(executar(&[], "1\n2 +\n").status.code(), Some(4));
+    assert_eq!(executar(&["1 / 0"], "").status.code(), Some(5));
+    // Com vários erros, vale o primeiro
+    assert_eq!(executar(&["1 / 0", "1 #"], "").status.code(), Some(5));
+    assert_eq!(executar(&["-f", "/caminho/que/nao/existe"], "").status.code(), Some(1));
+    // Uma expressão aninhada demais é um erro de sintaxe, e não esgota a pilha
+    let profunda = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+    assert_eq!(executar(&[], &profunda).status.code(), Some(4));
+    // Uma soma longa não se aninha, e é aceita
+    let resultado = executar(&[], &vec!["1"; 5000].join(" + "));
+    assert_eq!(saida(&resultado), "5000\n");
+    assert_eq!(resultado.status.code(), Some(0));
+
+    let resultado = executar(&["--talvez"], "");
+    assert_eq!(resultado.status.code(), Some(2));
+    assert!(erros(&resultado).starts_with("erro: opção desconhecida '--talvez'\n"));
+    assert_eq!(executar(&["-f"], "").status.code(), Some(2));
+    let resultado = executar(&["--help"], "");
+    assert!(saida(&resultado).starts_with("uso: operations"));
+    assert_eq!(resultado.status.code(), Some(0));
+}
+
+#[test]
+fn test_saida_fechada_antes_do_fim() {
+    let mut processo = Command::new(env!("CARGO_BIN_EXE_operations"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Como `operations | head -1`: quem lia a saída a fecha logo no começo
+    drop(processo.stdout.take());
+    // Mais resultados do que cabem no buffer do pipe; a escrita pode falhar
+    // quando o processo já terminou
+    let _ = processo.stdin.take().unwrap().write_all("1 + 1\n".repeat(100_000).as_bytes());
+    let resultado = processo.wait_with_output().unwrap();
+    assert!(erros(&resultado).is_empty(), "{}", erros(&resultado));
+    assert_eq!(resultado.status.code(), Some(0));
+}
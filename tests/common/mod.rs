@@ -0,0 +1,14 @@
+// Auxiliares compartilhados pelos arquivos de teste; cada arquivo usa só
+// uma parte deles
+#![allow(dead_code)]
+
+use operations::ast::parser::{Ast, ParseError, Parser};
+use operations::tokenize;
+
+pub fn parse(programa: &str) -> Ast {
+    Parser::new(tokenize(programa).unwrap()).parse().unwrap()
+}
+
+pub fn parse_erro(programa: &str) -> ParseError {
+    Parser::new(tokenize(programa).unwrap()).parse().unwrap_err()
+}
@@ -0,0 +1,126 @@
+use operations::ast::lexer::{Lexer, Token, TokenKind};
+use operations::tokenize;
+use operations::ast::parser::{ParseError, Parser};
+use operations::ast::valor::Value;
+use operations::ast::lexer::TextSpan;
+use operations::diagnostico::Diagnostic;
+use operations::fonte::{Posicao, SourceMap};
+
+
+// Mantém os tokens inválidos para que o parser os reporte
+fn tokens_com_erros(entrada: &str) -> Vec<Token> {
+    Lexer::new(entrada)
+        .filter(|token| token.kind != TokenKind::EspacoEmBranco)
+        .collect()
+}
+
+#[test]
+fn test_recuperacao_reporta_todos_os_erros() {
+    let erros = Parser::new(tokens_com_erros("(1 + * 2) $ 3 +")).parse_com_recuperacao().unwrap_err();
+
+    assert_eq!(erros.len(), 4);
+    assert!(matches!(erros[0], ParseError::OperandoAusente(_)));
+    assert_eq!(erros[0].span().start, 5);
+    assert!(matches!(erros[1], ParseError::TokenInesperado(_)));
+    assert_eq!(erros[1].span().literal, "$");
+    assert!(matches!(erros[2], ParseError::EntradaRestante(_)));
+    assert_eq!(erros[2].span().literal, "3");
+    assert!(matches!(erros[3], ParseError::OperandoAusente(_)));
+    assert_eq!(erros[3].span().start, 15);
+}
+
+#[test]
+fn test_recuperacao_sem_erros_devolve_a_arvore() {
+    let ast = Parser::new(tokenize("2 * (3 + 4)").unwrap()).parse_com_recuperacao().unwrap();
+    assert_eq!(ast.avaliar(), Ok(Value::Numero(14)));
+}
+
+#[test]
+fn test_renderizacao_com_sublinhado() {
+    let entrada = "1 + (2 * 3";
+    let erro = Parser::new(tokenize(entrada).unwrap()).parse().unwrap_err();
+    let texto = Diagnostic::from(&erro).renderizar(entrada);
+
+    assert_eq!(
+        texto,
+        "erro: parêntese aberto sem fechamento\n --> 1:5\n  |\n1 | 1 + (2 * 3\n  |     ^\n  = dica: feche o parêntese com ')'"
+    );
+}
+
+#[test]
+fn test_renderizacao_de_erro_de_avaliacao() {
+    let entrada = "7 + 8 / (4 - 4)";
+    let erro = Parser::new(tokenize(entrada).unwrap()).parse().unwrap().avaliar().unwrap_err();
+    let texto = Diagnostic::from(&erro).renderizar(entrada);
+
+    assert!(texto.starts_with("erro: divisão por zero\n"));
+    assert!(texto.contains("\n  |     ^^^^^^^^^^^\n"));
+}
+
+#[test]
+fn test_source_map_linha_e_coluna() {
+    let fonte = "1 + 2\n\n(3 * 4) / 0\r\n5";
+    let mapa = SourceMap::new(fonte).com_arquivo("calc.txt");
+
+    assert_eq!(mapa.posicao(0), Posicao { linha: 1, coluna: 1 });
+    assert_eq!(mapa.posicao(4), Posicao { linha: 1, coluna: 5 });
+    assert_eq!(mapa.posicao(6), Posicao { linha: 2, coluna: 1 });
+    assert_eq!(mapa.posicao(15), Posicao { linha: 3, coluna: 9 });
+    assert_eq!(mapa.posicao(fonte.len()), Posicao { linha: 4, coluna: 2 });
+    assert_eq!(mapa.linha(3), "(3 * 4) / 0");
+
+    let span = TextSpan::new(17, 18, "0".to_string());
+    assert_eq!(mapa.localizar(&span).to_string(), "calc.txt:3:11");
+}
+
+#[test]
+fn test_renderizacao_em_entrada_com_varias_linhas() {
+    let fonte = "1 + 1\n2 * (3\n";
+    let span = TextSpan::new(10, 11, "(".to_string());
+    let texto = Diagnostic::new("parêntese aberto sem fechamento", span)
+        .renderizar_com(&SourceMap::new(fonte).com_arquivo("calc.txt"));
+
+    assert_eq!(
+        texto,
+        "erro: parêntese aberto sem fechamento\n --> calc.txt:2:5\n  |\n2 | 2 * (3\n  |     ^"
+    );
+
+    // Um trecho de um arquivo maior mantém a numeração das linhas do arquivo
+    let texto = Diagnostic::new("parêntese aberto sem fechamento", TextSpan::new(4, 5, "(".to_string()))
+        .renderizar_com(&SourceMap::new("2 * (3").com_arquivo("calc.txt").com_primeira_linha(12));
+    assert_eq!(
+        texto,
+        "erro: parêntese aberto sem fechamento\n  --> calc.txt:12:5\n   |\n12 | 2 * (3\n   |     ^"
+    );
+}
+
+#[test]
+fn test_sublinhado_alinhado_apos_caracteres_multibyte() {
+    let entrada = "2 × 3 ÷ €";
+    let erro = tokenize(entrada).unwrap_err();
+    let texto = Diagnostic::from(&erro).renderizar(entrada);
+
+    assert!(texto.contains(" --> 1:9\n"));
+    assert!(texto.contains("\n  |         ^\n"));
+}
+
+#[test]
+fn test_erro_lexico_no_fim_da_entrada() {
+    let entrada = "1 + 2 @";
+    let erro = tokenize(entrada).unwrap_err();
+    let texto = Diagnostic::from(&erro).renderizar(entrada);
+
+    assert!(texto.starts_with("erro: caractere inválido '@'\n --> 1:7\n"));
+}
+
+#[test]
+fn test_renderizacao_de_literal_fora_do_dominio() {
+    let entrada = "1 + 2.5";
+    let erro = Parser::new(tokenize(entrada).unwrap()).parse().unwrap_err();
+    let texto = Diagnostic::from(&erro).renderizar(entrada);
+
+    assert_eq!(
+        texto,
+        "erro: literal inválido '2.5': número decimal em uma avaliação inteira\n --> 1:5\n  |\n1 | 1 + 2.5\n  |     ^^^\n  = dica: use a avaliação real para trabalhar com números decimais"
+    );
+}
@@ -0,0 +1,104 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use operations::ast::avaliador::{EvalError, Rounding};
+use operations::ast::parser::{ParseError, Parser};
+use operations::numero::{ErroAritmetico, Number, Resultado};
+use operations::tokenize;
+
+// Inteiros módulo 7, um domínio definido fora do crate
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Mod7(i64);
+
+// Restos módulo 7 não têm ordem: só valores iguais são comparáveis
+impl PartialOrd for Mod7 {
+    fn partial_cmp(&self, outro: &Self) -> Option<Ordering> {
+        (self == outro).then_some(Ordering::Equal)
+    }
+}
+
+impl Display for Mod7 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (mod 7)", self.0)
+    }
+}
+
+impl Number for Mod7 {
+    fn de_inteiro(valor: i64) -> Self {
+        Mod7(valor.rem_euclid(7))
+    }
+
+    fn somar(&self, outro: &Self) -> Resultado<Self> {
+        Ok(Self::de_inteiro(self.0 + outro.0))
+    }
+
+    fn subtrair(&self, outro: &Self) -> Resultado<Self> {
+        Ok(Self::de_inteiro(self.0 - outro.0))
+    }
+
+    fn multiplicar(&self, outro: &Self) -> Resultado<Self> {
+        Ok(Self::de_inteiro(self.0 * outro.0))
+    }
+
+    // Multiplica pelo inverso, que existe para todo valor não nulo
+    fn dividir(&self, outro: &Self, _arredondamento: Rounding) -> Resultado<Self> {
+        if outro.0 == 0 {
+            return Err(ErroAritmetico::DivisaoPorZero);
+        }
+        self.multiplicar(&outro.potencia(&Mod7(5))?)
+    }
+
+    fn quociente(&self, outro: &Self, arredondamento: Rounding) -> Resultado<Self> {
+        self.dividir(outro, arredondamento)
+    }
+
+    fn resto(&self, outro: &Self, _arredondamento: Rounding) -> Resultado<Self> {
+        if outro.0 == 0 {
+            return Err(ErroAritmetico::DivisaoPorZero);
+        }
+        Ok(Mod7(0))
+    }
+
+    fn potencia(&self, expoente: &Self) -> Resultado<Self> {
+        Ok(Self::de_inteiro(self.0.pow(expoente.0 as u32)))
+    }
+
+    fn negar(&self) -> Resultado<Self> {
+        Ok(Self::de_inteiro(-self.0))
+    }
+}
+
+fn parse(expressao: &str) -> Result<operations::ast::parser::Ast<Mod7>, ParseError> {
+    Parser::<Mod7>::com_dominio(tokenize(expressao).unwrap()).parse()
+}
+
+#[test]
+fn test_dominio_externo() {
+    assert_eq!(parse("3 * 5 + 1").unwrap().avaliar().unwrap(), Mod7(2));
+    assert_eq!(parse("-1").unwrap().avaliar().unwrap(), Mod7(6));
+    assert_eq!(parse("3 / 5").unwrap().avaliar().unwrap(), Mod7(2));
+    assert!(matches!(parse("1 / 7").unwrap().avaliar(), Err(EvalError::DivisaoPorZero { .. })));
+}
+
+#[test]
+fn test_rastro_no_dominio_externo() {
+    let rastro = parse("(4 + 5) * 2").unwrap().eval_step();
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(expressoes, vec!["(2 (mod 7) * 2 (mod 7))", "4 (mod 7)"]);
+    assert_eq!(rastro.passos[0].operandos, vec![Mod7(4), Mod7(5)]);
+}
+
+#[test]
+fn test_literais_recusados_pelo_dominio() {
+    let erro = parse("1 + 0.5").unwrap_err();
+    assert!(matches!(erro, ParseError::LiteralInvalido(_, ErroAritmetico::LiteralDecimal)));
+    let erro = parse("2 * 99999999999999999999").unwrap_err();
+    assert!(matches!(erro, ParseError::LiteralInvalido(_, ErroAritmetico::Overflow)));
+    assert_eq!(erro.span().literal, "99999999999999999999");
+
+    let erros = Parser::<Mod7>::com_dominio(tokenize("0.5 + 1 +").unwrap())
+        .parse_com_recuperacao()
+        .unwrap_err();
+    assert_eq!(erros.len(), 2);
+    assert!(matches!(erros[0], ParseError::LiteralInvalido(..)));
+    assert!(matches!(erros[1], ParseError::OperandoAusente(_)));
+}
@@ -0,0 +1,370 @@
+mod common;
+
+use common::{parse, parse_erro};
+use operations::ast::ambiente::Environment;
+use operations::ast::avaliador::{EvalError, Evaluator, LIMITE_DE_RECURSAO};
+use operations::ast::funcoes::{Arity, FunctionRegistry};
+use operations::ast::lexer::TokenKind;
+use operations::ast::parser::{Node, ParseError, Parser, Statement};
+use operations::ast::valor::Value;
+use operations::diagnostico::Diagnostic;
+use operations::numero::inteiro::BigInt;
+use operations::numero::racional::Rational;
+use operations::numero::{ErroAritmetico, Number};
+use operations::tokenize;
+
+fn avaliar_racional(expressao: &str) -> Result<Value<Rational>, EvalError> {
+    Parser::<Rational>::com_dominio(tokenize(expressao).unwrap()).parse().unwrap().avaliar()
+}
+
+#[test]
+fn test_funcoes_embutidas() {
+    assert_eq!(parse("abs(-7) + abs(3)").avaliar(), Ok(Value::Numero(10)));
+    assert_eq!(parse("min(4, -2, 9) * max(1, 8, 3)").avaliar(), Ok(Value::Numero(-16)));
+    assert_eq!(parse("max(5)").avaliar(), Ok(Value::Numero(5)));
+    assert_eq!(parse("gcd(12, -18)").avaliar(), Ok(Value::Numero(6)));
+    assert_eq!(parse("lcm(4, 6) + lcm(0, 5)").avaliar(), Ok(Value::Numero(12)));
+    assert_eq!(parse("sqrt(10) + sqrt(16)").avaliar(), Ok(Value::Numero(7)));
+    assert_eq!(parse("pow(2, 10) - 2 ^ 10").avaliar(), Ok(Value::Numero(0)));
+    // Argumentos podem ser expressões, inclusive outras chamadas
+    assert_eq!(parse("max(1 + 2 * 3, (4 - 1) ^ 2, abs(min(-20, 5)))").avaliar(), Ok(Value::Numero(20)));
+}
+
+#[test]
+fn test_funcoes_em_outros_dominios() {
+    assert_eq!(avaliar_racional("gcd(1/2, 3/4)"), Ok(Value::Numero(Rational::new(1, 4).unwrap())));
+    assert_eq!(avaliar_racional("sqrt(9/4)"), Ok(Value::Numero(Rational::new(3, 2).unwrap())));
+    assert!(matches!(avaliar_racional("sqrt(2)"), Err(EvalError::ResultadoIrracional { .. })));
+
+    let real = Parser::<f64>::com_dominio(tokenize("sqrt(2) * sqrt(2)").unwrap()).parse().unwrap();
+    assert!((real.avaliar().unwrap().numero().unwrap() - 2.0).abs() < 1e-12);
+
+    let grande = Parser::<BigInt>::com_dominio(tokenize("sqrt(10 ^ 40 + 1)").unwrap()).parse().unwrap();
+    assert_eq!(grande.avaliar().unwrap().to_string(), format!("1{}", "0".repeat(20)));
+}
+
+#[test]
+fn test_chamada_vira_no_da_arvore() {
+    let ast = parse("1 + max(a, 2 * b)");
+    match ast.raiz().unwrap() {
+        Node::BinaryOp { right, .. } => match right.as_ref() {
+            Node::Chamada { nome, args, span } => {
+                assert_eq!(nome, "max");
+                assert_eq!(args.len(), 2);
+                assert_eq!((span.start, span.end), (4, 17));
+            }
+            outro => panic!("esperava uma chamada, obteve {:?}", outro),
+        },
+        outro => panic!("esperava uma operação binária, obteve {:?}", outro),
+    }
+    assert_eq!(ast.raiz().unwrap().para_string(), "(1 + max(a, (2 * b)))");
+    // Quebras de linha dentro da chamada não encerram a instrução
+    assert_eq!(parse("max(1,\n 2)\n").avaliar(), Ok(Value::Numero(2)));
+}
+
+#[test]
+fn test_erros_de_sintaxe_em_chamadas() {
+    assert!(matches!(parse_erro("max(1, 2"), ParseError::ParentesesDesbalanceados(t) if t.kind == TokenKind::ParentesesEsquerdo));
+    assert!(matches!(parse_erro("max(1,)"), ParseError::OperandoAusente(t) if t.kind == TokenKind::ParentesesDireito));
+    assert!(matches!(parse_erro("max(, 1)"), ParseError::OperandoAusente(t) if t.kind == TokenKind::Virgula));
+    assert!(matches!(parse_erro("max(1 2)"), ParseError::TokenInesperado(t) if t.span.literal == "2"));
+
+    let erro = parse_erro("1, 2");
+    assert!(matches!(&erro, ParseError::TokenInesperado(t) if t.kind == TokenKind::Virgula));
+    let texto = Diagnostic::from(&erro).renderizar("1, 2");
+    assert!(texto.ends_with("= dica: a vírgula só separa os argumentos de uma chamada, como em 'max(1, 2)'"));
+
+    // Em modo de recuperação, a chamada não fechada não esconde os demais erros
+    let erros = Parser::new(tokenize("abs(1 +; 2 3").unwrap()).parse_com_recuperacao().unwrap_err();
+    let mensagens: Vec<String> = erros.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        mensagens,
+        [
+            "parêntese aberto sem fechamento",
+            "operando ausente junto a ';'",
+            "entrada restante após a expressão: '3'",
+        ]
+    );
+}
+
+#[test]
+fn test_aridade_incorreta() {
+    let entrada = "pow(2, 3, 4, 5)";
+    let erro = parse(entrada).avaliar().unwrap_err();
+    assert!(matches!(
+        &erro,
+        EvalError::AridadeIncorreta { nome, esperada: Arity::Exata(2), recebida: 4, .. } if nome == "pow"
+    ));
+    // O span cobre só os argumentos excedentes
+    assert_eq!((erro.span().start, erro.span().end), (10, 14));
+    let texto = Diagnostic::from(&erro).renderizar(entrada);
+    assert!(texto.starts_with("erro: a função 'pow' espera 2 argumentos, mas recebeu 4\n"));
+    assert!(texto.contains("\n  |           ^^^^\n"));
+    assert!(texto.ends_with("= dica: remova os argumentos excedentes"));
+
+    let erro = parse("1 + sqrt()").avaliar().unwrap_err();
+    assert_eq!(erro.to_string(), "a função 'sqrt' espera 1 argumento, mas recebeu 0");
+    assert_eq!((erro.span().start, erro.span().end), (4, 10));
+    assert_eq!(
+        parse("min()").avaliar().unwrap_err().to_string(),
+        "a função 'min' espera pelo menos 1 argumento, mas recebeu 0"
+    );
+}
+
+#[test]
+fn test_funcao_indefinida_e_erros_nos_argumentos() {
+    let erro = parse("2 * media(1, 2)").avaliar().unwrap_err();
+    assert_eq!(
+        erro,
+        EvalError::FuncaoIndefinida {
+            nome: "media".to_string(),
+            span: erro.span().clone(),
+        }
+    );
+    assert_eq!((erro.span().start, erro.span().end), (4, 15));
+    let texto = Diagnostic::from(&erro).renderizar("2 * media(1, 2)");
+    assert!(texto.ends_with("= dica: verifique o nome, ou defina a função antes de chamá-la"));
+    // Com o ambiente, a dica lista também as funções registradas e as definidas no programa
+    let mut ambiente = Environment::new().com_registro(registro());
+    let programa = parse("f(x) = x; abs(x) = -x; tax(1)");
+    let erro = Evaluator::new().executar(&programa, &mut ambiente).unwrap_err();
+    let dica = Diagnostic::de_avaliacao(&erro, &ambiente).dica.unwrap();
+    assert_eq!(dica, "as funções disponíveis são abs, min, max, gcd, lcm, sqrt, pow, inverso, soma, taxa, f");
+
+    // A aridade é verificada antes de avaliar os argumentos
+    assert!(matches!(parse("abs(1 / 0, 2)").avaliar(), Err(EvalError::AridadeIncorreta { .. })));
+    assert!(matches!(parse("abs(1 / 0)").avaliar(), Err(EvalError::DivisaoPorZero { .. })));
+    assert!(matches!(parse("sqrt(-4)").avaliar(), Err(EvalError::ResultadoIndefinido { .. })));
+}
+
+#[test]
+fn test_rastro_de_chamadas() {
+    let rastro = parse("max(2 * 3, -4) + abs(-1)").eval_step();
+    assert_eq!(rastro.expressao, "(max((2 * 3), -4) + abs(-1))");
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(expressoes, ["(max(6, -4) + abs(-1))", "(6 + abs(-1))", "(6 + 1)", "7"]);
+    assert_eq!(rastro.passos[1].operandos, [6, -4]);
+    assert_eq!(rastro.resultado, Ok(Value::Numero(7)));
+
+    let rastro = parse("1 + gcd(4, 6, 8)").eval_step();
+    assert!(rastro.passos.is_empty());
+    assert!(matches!(rastro.resultado, Err(EvalError::AridadeIncorreta { .. })));
+}
+
+#[test]
+fn test_funcoes_definidas_no_programa() {
+    assert_eq!(parse("f(x) = x * x + 1; f(3) + f(4)").avaliar(), Ok(Value::Numero(27)));
+    assert_eq!(parse("area(b, h) = b * h / 2\narea(6, 4)").avaliar(), Ok(Value::Numero(12)));
+    assert_eq!(parse("dobro(x) = 2 * x; quad(x) = dobro(dobro(x)); quad(5)").avaliar(), Ok(Value::Numero(20)));
+    // Funções sem parâmetros e definições que usam as embutidas
+    assert_eq!(parse("k() = 7; hip2(a, b) = pow(a, 2) + pow(b, 2); hip2(3, 4) - k()").avaliar(), Ok(Value::Numero(18)));
+    // Uma definição de mesmo nome substitui a embutida
+    assert_eq!(parse("abs(x) = x; abs(-2)").avaliar(), Ok(Value::Numero(-2)));
+    // Variável e função podem ter o mesmo nome
+    assert_eq!(parse("f = 10; f(x) = x + f; f(f)").avaliar(), Ok(Value::Numero(20)));
+}
+
+#[test]
+fn test_definicao_vira_instrucao() {
+    let ast = parse("g(a, b) = a - b; g(5, 2)");
+    match &ast.instrucoes()[0] {
+        Statement::Definicao { nome, parametros, corpo, span } => {
+            assert_eq!(nome, "g");
+            assert_eq!(parametros, &["a", "b"]);
+            assert_eq!(corpo.para_string(), "(a - b)");
+            assert_eq!((span.start, span.end), (0, 15));
+        }
+        outra => panic!("esperava uma definição, obteve {:?}", outra),
+    }
+    assert_eq!(ast.instrucoes()[0].para_string(), "g(a, b) = (a - b)");
+    // Definições não têm valor: a raiz é a última expressão
+    assert_eq!(ast.raiz().unwrap().para_string(), "g(5, 2)");
+    assert_eq!(parse("x = 4; f(y) = y").avaliar(), Ok(Value::Numero(4)));
+}
+
+#[test]
+fn test_escopo_dos_parametros() {
+    // O parâmetro esconde a variável global de mesmo nome
+    let ambiente = Environment::new().com("x", 100);
+    assert_eq!(parse("f(x) = x + 1; f(1)").avaliar_em(&ambiente), Ok(Value::Numero(2)));
+    // O corpo enxerga as variáveis globais, mas não os parâmetros de quem chama
+    assert_eq!(parse("g(y) = x + y; f(x) = g(1); f(5)").avaliar_em(&ambiente), Ok(Value::Numero(101)));
+    let erro = parse("g() = y; f(y) = g(); f(1)").avaliar().unwrap_err();
+    assert!(matches!(erro, EvalError::VariavelIndefinida { nome, .. } if nome == "y"));
+    // As globais são lidas no momento da chamada
+    assert_eq!(parse("f() = taxa * 2; taxa = 3; f()").avaliar(), Ok(Value::Numero(6)));
+}
+
+#[test]
+fn test_executar_grava_as_funcoes() {
+    let mut ambiente = Environment::new();
+    assert_eq!(parse("sq(n) = n * n; cubo(n) = sq(n) * n").executar(&mut ambiente), Ok(Value::Numero(0)));
+    assert_eq!(ambiente.nomes_de_funcoes(), ["cubo", "sq"]);
+    assert!(ambiente.nomes().is_empty());
+    assert_eq!(ambiente.funcao("sq").unwrap().parametros(), ["n"]);
+    // A sessão seguinte continua enxergando as definições
+    assert_eq!(parse("cubo(3) - sq(3)").executar(&mut ambiente), Ok(Value::Numero(18)));
+}
+
+#[test]
+fn test_aridade_de_funcoes_definidas() {
+    let entrada = "f(x) = x; f(1, 2)";
+    let erro = parse(entrada).avaliar().unwrap_err();
+    assert!(matches!(
+        &erro,
+        EvalError::AridadeIncorreta { nome, esperada: Arity::Exata(1), recebida: 2, .. } if nome == "f"
+    ));
+    assert_eq!((erro.span().start, erro.span().end), (15, 16));
+    // Chamar antes de definir ainda é um erro
+    assert!(matches!(parse("f(1); f(x) = x").avaliar(), Err(EvalError::FuncaoIndefinida { .. })));
+}
+
+#[test]
+fn test_recursao_excessiva() {
+    let entrada = "f(n) = f(n + 1) * 2; f(0)";
+    let erro = parse(entrada).avaliar().unwrap_err();
+    assert_eq!(
+        erro,
+        EvalError::RecursaoExcessiva {
+            nome: "f".to_string(),
+            limite: LIMITE_DE_RECURSAO,
+            span: erro.span().clone(),
+        }
+    );
+    // O span aponta para a chamada recursiva dentro do corpo
+    assert_eq!((erro.span().start, erro.span().end), (7, 15));
+    let texto = Diagnostic::from(&erro).renderizar(entrada);
+    assert!(texto.starts_with("erro: recursão excessiva em 'f': mais de 100 chamadas aninhadas\n"));
+
+    // O limite é configurável e conta só chamadas aninhadas
+    let avaliador = Evaluator::new().com_limite_de_recursao(3);
+    let mut ambiente = Environment::new();
+    let profunda = parse("a(x) = b(x) + 1; b(x) = c(x) + 1; c(x) = d(x) + 1; d(x) = x; a(0)");
+    assert!(matches!(avaliador.executar(&profunda, &mut ambiente), Err(EvalError::RecursaoExcessiva { limite: 3, .. })));
+    let rasa = parse("a(x) = b(x) + b(x) + b(x); b(x) = x + 1; a(a(a(0)))");
+    assert_eq!(avaliador.executar(&rasa, &mut Environment::new()), Ok(Value::Numero(39)));
+}
+
+#[test]
+fn test_recursao_profunda_com_limite_alto() {
+    // As chamadas em andamento não ocupam a pilha nativa, e o limite configurado vale por inteiro
+    let avaliador = Evaluator::new().com_limite_de_recursao(100_000);
+    assert_eq!(avaliador.limite_de_recursao(), 100_000);
+    let contagem = "f(n) = se n == 0 entao 0 senao 1 + f(n - 1)";
+    // `f(n)` chama a si mesma n + 1 vezes, até `f(0)`
+    let programa = parse(&format!("{}; f(99999)", contagem));
+    assert_eq!(avaliador.executar(&programa, &mut Environment::new()), Ok(Value::Numero(99_999)));
+    let programa = parse(&format!("{}; f(100000)", contagem));
+    let erro = avaliador.executar(&programa, &mut Environment::new()).unwrap_err();
+    assert!(matches!(erro, EvalError::RecursaoExcessiva { limite: 100_000, .. }));
+    assert_eq!(erro.to_string(), "recursão excessiva em 'f': mais de 100000 chamadas aninhadas");
+}
+
+#[test]
+fn test_erros_de_sintaxe_em_definicoes() {
+    let erro = parse_erro("f(x, y, x) = x");
+    assert!(matches!(&erro, ParseError::ParametroRepetido(t) if t.span.start == 8));
+    let texto = Diagnostic::from(&erro).renderizar("f(x, y, x) = x");
+    assert!(texto.starts_with("erro: parâmetro repetido 'x'\n"));
+    assert!(texto.ends_with("= dica: dê um nome diferente a cada parâmetro"));
+
+    assert!(matches!(parse_erro("f(x y) = x"), ParseError::TokenInesperado(t) if t.span.literal == "y"));
+    assert!(matches!(parse_erro("f(x,) = x"), ParseError::OperandoAusente(t) if t.kind == TokenKind::ParentesesDireito));
+    assert!(matches!(parse_erro("f(x) = "), ParseError::OperandoAusente(t) if t.kind == TokenKind::FimDeArquivo));
+    // Com argumentos que não são nomes, não é uma definição
+    assert!(matches!(parse_erro("f(1) = 2"), ParseError::TokenInesperado(t) if t.kind == TokenKind::Igual));
+}
+
+#[test]
+fn test_rastro_com_funcoes_definidas() {
+    let rastro = parse("f(x) = x * x + 1; f(2 + 1) * 2").eval_step();
+    assert_eq!(rastro.expressao, "f(x) = ((x * x) + 1); (f((2 + 1)) * 2)");
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(
+        expressoes,
+        [
+            "f(x) = ((x * x) + 1); (f(3) * 2)",
+            "f(x) = ((x * x) + 1); (10 * 2)",
+            "f(x) = ((x * x) + 1); 20",
+        ]
+    );
+    assert_eq!(rastro.passos[1].operandos, [3]);
+    assert_eq!(rastro.resultado, Ok(Value::Numero(20)));
+}
+
+fn registro() -> FunctionRegistry {
+    FunctionRegistry::new()
+        .com("taxa", Arity::Exata(1), |args: &[i64]| Ok(args[0] * 5 / 100))
+        .com("soma", Arity::AoMenos(0), |args: &[i64]| Ok(args.iter().sum()))
+        .com("inverso", Arity::Exata(1), |args: &[i64]| match args[0] {
+            0 => Err(ErroAritmetico::DivisaoPorZero),
+            x => Ok(1 / x),
+        })
+}
+
+#[test]
+fn test_funcoes_registradas() {
+    let ambiente = Environment::new().com_registro(registro()).com("valor", 2000);
+    assert_eq!(parse("taxa(valor) + 1").avaliar_em(&ambiente), Ok(Value::Numero(101)));
+    assert_eq!(parse("soma() + soma(1, 2, 3, 4)").avaliar_em(&ambiente), Ok(Value::Numero(10)));
+    // Funções registradas convivem com as embutidas e com as definidas no programa
+    assert_eq!(parse("f(x) = taxa(x) * 2; max(f(100), soma(3, 4))").avaliar_em(&ambiente), Ok(Value::Numero(10)));
+    assert_eq!(ambiente.registro().nomes(), ["inverso", "soma", "taxa"]);
+    assert_eq!(ambiente.registro().aridade("soma"), Some(Arity::AoMenos(0)));
+    // Sem o registro, o nome é desconhecido
+    assert!(matches!(parse("taxa(1)").avaliar(), Err(EvalError::FuncaoIndefinida { .. })));
+}
+
+#[test]
+fn test_prioridade_entre_funcoes() {
+    let mut ambiente = Environment::new().com_registro(FunctionRegistry::new().com(
+        "abs",
+        Arity::Exata(1),
+        |_: &[i64]| Ok(42),
+    ));
+    // A registrada substitui a embutida, e a definida no programa substitui as duas
+    assert_eq!(parse("abs(-1)").avaliar_em(&ambiente), Ok(Value::Numero(42)));
+    assert_eq!(parse("abs(x) = x; abs(-1)").avaliar_em(&ambiente), Ok(Value::Numero(-1)));
+
+    assert!(ambiente.registro_mut().remover("abs"));
+    assert!(!ambiente.registro_mut().remover("abs"));
+    assert_eq!(parse("abs(-1)").avaliar_em(&ambiente), Ok(Value::Numero(1)));
+    assert!(!ambiente.registro_mut().registrar("dobro", Arity::Exata(1), |args: &[i64]| Ok(args[0] * 2)));
+    assert_eq!(parse("dobro(21)").avaliar_em(&ambiente), Ok(Value::Numero(42)));
+}
+
+#[test]
+fn test_erros_de_funcoes_registradas() {
+    let ambiente = Environment::new().com_registro(registro());
+    // A aridade é verificada antes de chamar a função do programa hospedeiro
+    let erro = parse("taxa(1, 2)").avaliar_em(&ambiente).unwrap_err();
+    assert!(matches!(erro, EvalError::AridadeIncorreta { esperada: Arity::Exata(1), recebida: 2, .. }));
+    assert_eq!((erro.span().start, erro.span().end), (8, 9));
+
+    // O erro devolvido pela função recebe o span da chamada
+    let erro = parse("1 + inverso(2 - 2)").avaliar_em(&ambiente).unwrap_err();
+    assert!(matches!(erro, EvalError::DivisaoPorZero { .. }));
+    assert_eq!((erro.span().start, erro.span().end), (4, 18));
+}
+
+#[test]
+fn test_registro_em_outro_dominio() {
+    // Arredonda para a quantidade de casas pedida, como em arredondar(x, 2)
+    let arredondar = |args: &[Rational]| {
+        let escala = Rational::inteiro(10).potencia(&args[1])?;
+        let escalado = args[0].multiplicar(&escala)?.somar(&Rational::new(1, 2).unwrap())?;
+        Rational::inteiro(escalado.piso()).dividir(&escala, Default::default())
+    };
+    let ambiente = Environment::new().com_registro(FunctionRegistry::new().com("arredondar", Arity::Exata(2), arredondar));
+    let ast = Parser::<Rational>::com_dominio(tokenize("arredondar(2 / 3, 2)").unwrap()).parse().unwrap();
+    assert_eq!(ast.avaliar_em(&ambiente), Ok(Value::Numero(Rational::new(67, 100).unwrap())));
+}
+
+#[test]
+fn test_rastro_com_funcoes_registradas() {
+    let ambiente = Environment::new().com_registro(registro());
+    let rastro = Evaluator::new().rastrear_em(&parse("taxa(1000 * 2) - 1"), &ambiente);
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(expressoes, ["(taxa(2000) - 1)", "(100 - 1)", "99"]);
+    assert_eq!(rastro.passos[1].operandos, [2000]);
+}
@@ -0,0 +1,156 @@
+use operations::ast::avaliador::{EvalError, Evaluator, Rounding};
+use operations::ast::parser::{ParseError, Parser};
+use operations::ast::valor::Value;
+use operations::numero::inteiro::BigInt;
+use operations::numero::racional::Rational;
+use operations::numero::ErroAritmetico;
+use operations::tokenize;
+
+fn grande(texto: &str) -> BigInt {
+    BigInt::de_digitos(texto).unwrap()
+}
+
+fn avaliar(expressao: &str) -> Result<Value<BigInt>, EvalError> {
+    Parser::<BigInt>::com_dominio(tokenize(expressao).unwrap()).parse().unwrap().avaliar()
+}
+
+#[test]
+fn test_leitura_e_escrita() {
+    for texto in ["0", "7", "-42", "4294967296", "-18446744073709551616", "123456789012345678901234567890"] {
+        assert_eq!(grande(texto).to_string(), texto);
+    }
+    assert_eq!(grande("-0"), BigInt::zero());
+    assert_eq!(grande("+0001000000000").to_string(), "1000000000");
+    assert!(BigInt::de_digitos("").is_none());
+    assert!(BigInt::de_digitos("12a").is_none());
+    assert_eq!(BigInt::from(i64::MIN).to_string(), "-9223372036854775808");
+}
+
+#[test]
+fn test_conversoes() {
+    assert_eq!(BigInt::from(i64::MIN).para_i64(), Some(i64::MIN));
+    assert_eq!(grande("9223372036854775807").para_i64(), Some(i64::MAX));
+    assert_eq!(grande("9223372036854775808").para_i64(), None);
+    assert_eq!(grande("-12345678901234567890").para_f64(), -12345678901234567890.0);
+}
+
+// Confere as operações contra i128 em valores que atravessam várias palavras
+#[test]
+fn test_operacoes_contra_i128() {
+    let valores: [i128; 8] = [
+        0,
+        1,
+        -7,
+        4_294_967_295,
+        -4_294_967_296,
+        18_446_744_073_709_551_557,
+        -170_141_183_460_469_231_731,
+        85_070_591_730_234_615_847_396_907_784_232_501_249,
+    ];
+    for &a in &valores {
+        for &b in &valores {
+            let (x, y) = (grande(&a.to_string()), grande(&b.to_string()));
+            assert_eq!((&x + &y).to_string(), (a + b).to_string());
+            assert_eq!((&x - &y).to_string(), (a - b).to_string());
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+            if let Some(produto) = a.checked_mul(b) {
+                assert_eq!((&x * &y).to_string(), produto.to_string());
+            }
+            if b != 0 {
+                let (quociente, resto) = x.checked_div_rem(&y).unwrap();
+                assert_eq!(quociente.to_string(), (a / b).to_string(), "{} / {}", a, b);
+                assert_eq!(resto.to_string(), (a % b).to_string(), "{} % {}", a, b);
+            } else {
+                assert!(x.checked_div_rem(&y).is_none());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_divisao_longa() {
+    let dividendo = grande("2").pow(300);
+    let divisor = &grande("3").pow(90) + &BigInt::from(1);
+    let (quociente, resto) = dividendo.checked_div_rem(&divisor).unwrap();
+    assert_eq!(&(&quociente * &divisor) + &resto, dividendo);
+    assert!(resto < divisor);
+}
+
+#[test]
+fn test_avaliacao_sem_overflow() {
+    assert_eq!(avaliar("9223372036854775807 + 1").unwrap(), grande("9223372036854775808"));
+    assert_eq!(avaliar("2 ^ 100").unwrap(), grande("1267650600228229401496703205376"));
+    assert_eq!(
+        avaliar("-147799088242 * 42714523 * 1000000007").unwrap(),
+        grande("-6313167598284111444643569962"),
+    );
+    assert_eq!(avaliar("123456789012345678901234567890 // 10 ^ 20").unwrap(), grande("1234567890"));
+    assert_eq!(avaliar("(-1) ^ 99999999999999999999").unwrap(), BigInt::from(-1));
+}
+
+#[test]
+fn test_convencoes_de_arredondamento() {
+    let avaliar_com = |expressao: &str, arredondamento| {
+        let ast = Parser::<BigInt>::com_dominio(tokenize(expressao).unwrap()).parse().unwrap();
+        Evaluator::new()
+            .com_arredondamento(arredondamento)
+            .avaliar(ast.raiz().unwrap())
+            .unwrap()
+    };
+    let casos = [
+        ("-7 / 2", [-3, -4, -4]),
+        ("7 / -2", [-3, -4, -3]),
+        ("-7 % 2", [-1, 1, 1]),
+        ("7 % -2", [1, -1, 1]),
+        ("-7 % -2", [-1, -1, 1]),
+    ];
+    for (expressao, esperados) in casos {
+        let obtidos = [Rounding::Truncar, Rounding::Piso, Rounding::Euclides].map(|arredondamento| avaliar_com(expressao, arredondamento));
+        assert_eq!(obtidos, esperados.map(BigInt::from), "{}", expressao);
+    }
+}
+
+#[test]
+fn test_potencias_pequenas_iguais_em_todos_os_dominios() {
+    let casos = [("0 ^ 0", "1"), ("0 ^ 3", "0"), ("1 ^ 0", "1"), ("(-1) ^ 0", "1"), ("(-1) ^ 3", "-1"), ("5 ^ 0", "1")];
+    for (expressao, esperado) in casos {
+        let tokens = tokenize(expressao).unwrap();
+        let obtidos = [
+            Parser::new(tokens.clone()).parse().unwrap().avaliar().unwrap().to_string(),
+            Parser::<f64>::com_dominio(tokens.clone()).parse().unwrap().avaliar().unwrap().to_string(),
+            Parser::<Rational>::com_dominio(tokens.clone()).parse().unwrap().avaliar().unwrap().to_string(),
+            Parser::<BigInt>::com_dominio(tokens).parse().unwrap().avaliar().unwrap().to_string(),
+        ];
+        assert_eq!(obtidos, [esperado; 4], "{}", expressao);
+    }
+}
+
+#[test]
+fn test_erros_de_inteiros_grandes() {
+    assert!(matches!(avaliar("10 ^ 40 / 0"), Err(EvalError::DivisaoPorZero { .. })));
+    assert!(matches!(avaliar("2 ^ -1"), Err(EvalError::ExpoenteNegativo { .. })));
+    // Resultados gigantescos são recusados em vez de esgotar a memória
+    assert!(matches!(avaliar("9 ^ 9 ^ 9"), Err(EvalError::Overflow { .. })));
+}
+
+#[test]
+fn test_literal_grande_em_outros_dominios() {
+    let tokens = tokenize("99999999999999999999 - 1").unwrap();
+    let erro = Parser::new(tokens.clone()).parse().unwrap_err();
+    assert!(matches!(erro, ParseError::LiteralInvalido(_, ErroAritmetico::Overflow)));
+    assert_eq!(erro.span().literal, "99999999999999999999");
+    let real = Parser::<f64>::com_dominio(tokens).parse().unwrap();
+    assert_eq!(real.avaliar().unwrap(), 1e20);
+}
+
+#[test]
+fn test_rastro_com_inteiros_grandes() {
+    let ast = Parser::<BigInt>::com_dominio(tokenize("(2 ^ 64 + 1) * 3").unwrap()).parse().unwrap();
+    let rastro = Evaluator::new().rastrear(&ast);
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(
+        expressoes,
+        vec!["((18446744073709551616 + 1) * 3)", "(18446744073709551617 * 3)", "55340232221128654851"]
+    );
+    assert_eq!(rastro.resultado.unwrap(), grande("55340232221128654851"));
+}
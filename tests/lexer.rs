@@ -0,0 +1,238 @@
+use operations::ast::lexer::{Lexer, LexError, Token, TokenKind};
+use operations::numero::inteiro::BigInt;
+use operations::tokenize;
+
+fn tokens(entrada: &str) -> Vec<Token> {
+    Lexer::new(entrada)
+        .filter(|token| !matches!(token.kind, TokenKind::EspacoEmBranco | TokenKind::FimDeArquivo))
+        .collect()
+}
+
+
+fn tipos(entrada: &str) -> Vec<TokenKind> {
+    tokens(entrada).into_iter().map(|token| token.kind).collect()
+}
+
+#[test]
+fn test_operadores_unicode() {
+    assert_eq!(
+        tipos("6 × 2 ÷ 3 − 1"),
+        vec![
+            TokenKind::Numero(6),
+            TokenKind::Asterisco,
+            TokenKind::Numero(2),
+            TokenKind::Barra,
+            TokenKind::Numero(3),
+            TokenKind::Menos,
+            TokenKind::Numero(1),
+        ]
+    );
+}
+
+#[test]
+fn test_spans_em_bytes_apos_caracteres_multibyte() {
+    let entrada = "12×(3÷4)";
+    let tokens = tokens(entrada);
+    let spans: Vec<(usize, usize)> = tokens.iter().map(|token| (token.span.start, token.span.end)).collect();
+
+    assert_eq!(spans, vec![(0, 2), (2, 4), (4, 5), (5, 6), (6, 8), (8, 9), (9, 10)]);
+    for token in &tokens {
+        assert_eq!(token.span.literal(entrada), token.span.literal);
+    }
+}
+
+#[test]
+fn test_caractere_invalido_multibyte() {
+    let entrada = "1 + € + 2";
+    let tokens = tokens(entrada);
+
+    assert_eq!(tokens[2].kind, TokenKind::Erro);
+    assert_eq!((tokens[2].span.start, tokens[2].span.end), (4, 7));
+    assert_eq!(tokens[2].span.literal(entrada), "€");
+    assert_eq!(tokens[4].kind, TokenKind::Numero(2));
+    assert_eq!(tokens[4].span.start, 10);
+}
+
+#[test]
+fn test_emoji_e_espacos_unicode() {
+    let entrada = "1\u{a0}+\u{2003}2 🙂";
+    let tokens = tokens(entrada);
+
+    assert_eq!(tokens.len(), 4);
+    assert_eq!(tokens[1].kind, TokenKind::Mais);
+    assert_eq!(tokens[3].kind, TokenKind::Erro);
+    assert_eq!(tokens[3].span.literal(entrada), "🙂");
+    assert_eq!(tokens[3].span.comprimento(), 4);
+}
+
+#[test]
+fn test_entrada_longa() {
+    let entrada = "1 × ".repeat(200_000) + "1";
+    assert_eq!(tokens(&entrada).len(), 400_001);
+}
+
+#[test]
+fn test_iterador_termina_com_fim_de_arquivo() {
+    let mut lexer = Lexer::new("1 +");
+    let tipos: Vec<TokenKind> = lexer.by_ref().map(|token| token.kind).collect();
+    assert_eq!(
+        tipos,
+        vec![
+            TokenKind::Numero(1),
+            TokenKind::EspacoEmBranco,
+            TokenKind::Mais,
+            TokenKind::FimDeArquivo,
+        ]
+    );
+    assert_eq!(lexer.next(), None);
+    assert_eq!(lexer.next(), None);
+}
+
+#[test]
+fn test_span_do_fim_de_arquivo() {
+    let tokens = tokenize("2 × 3").unwrap();
+    let fim = tokens.last().unwrap();
+    assert_eq!(fim.kind, TokenKind::FimDeArquivo);
+    assert_eq!((fim.span.start, fim.span.end), (6, 6));
+
+    let tokens = tokenize("").unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!((tokens[0].span.start, tokens[0].span.end), (0, 0));
+}
+
+#[test]
+fn test_tokenize_descarta_espacos_e_para_no_erro() {
+    let tipos: Vec<TokenKind> = tokenize(" (1) ").unwrap().into_iter().map(|token| token.kind).collect();
+    assert_eq!(
+        tipos,
+        vec![
+            TokenKind::ParentesesEsquerdo,
+            TokenKind::Numero(1),
+            TokenKind::ParentesesDireito,
+            TokenKind::FimDeArquivo,
+        ]
+    );
+
+    let LexError { token } = tokenize("1 + §").unwrap_err();
+    assert_eq!(token.span.literal, "§");
+    assert_eq!(token.span.start, 4);
+}
+
+#[test]
+fn test_operadores_de_potencia() {
+    let tokens = tokens("2**3^4*5");
+    let tipos: Vec<&TokenKind> = tokens.iter().map(|token| &token.kind).collect();
+    assert_eq!(
+        tipos,
+        vec![
+            &TokenKind::Numero(2),
+            &TokenKind::Potencia,
+            &TokenKind::Numero(3),
+            &TokenKind::Potencia,
+            &TokenKind::Numero(4),
+            &TokenKind::Asterisco,
+            &TokenKind::Numero(5),
+        ]
+    );
+    assert_eq!(tokens[1].span.literal, "**");
+}
+
+#[test]
+fn test_operadores_de_divisao_e_resto() {
+    assert_eq!(
+        tipos("7 // 2 % 3 mod 4 / 5"),
+        vec![
+            TokenKind::Numero(7),
+            TokenKind::BarraDupla,
+            TokenKind::Numero(2),
+            TokenKind::Porcento,
+            TokenKind::Numero(3),
+            TokenKind::Mod,
+            TokenKind::Numero(4),
+            TokenKind::Barra,
+            TokenKind::Numero(5),
+        ]
+    );
+    let tokens = tokens("1 modulo 2");
+    assert_eq!(tokens[1].kind, TokenKind::Identificador("modulo".to_string()));
+    assert_eq!(tokens[1].span.literal, "modulo");
+}
+
+#[test]
+fn test_literais_decimais() {
+    assert_eq!(
+        tipos("2.75 .5 1e-3 2E+2 6.02e23 7"),
+        vec![
+            TokenKind::Decimal(2.75),
+            TokenKind::Decimal(0.5),
+            TokenKind::Decimal(0.001),
+            TokenKind::Decimal(200.0),
+            TokenKind::Decimal(6.02e23),
+            TokenKind::Numero(7),
+        ]
+    );
+}
+
+#[test]
+fn test_ponto_e_expoente_incompletos() {
+    assert_eq!(
+        tipos("3. 2e"),
+        vec![
+            TokenKind::Numero(3),
+            TokenKind::Erro,
+            TokenKind::Numero(2),
+            TokenKind::Identificador("e".to_string()),
+        ]
+    );
+    assert_eq!(tokens("1.5.2")[1].span.literal, ".2");
+}
+
+#[test]
+fn test_literais_inteiros_longos() {
+    let digitos = "1".repeat(60);
+    assert_eq!(
+        tipos(&format!("9223372036854775807 9223372036854775808 {}", digitos)),
+        vec![
+            TokenKind::Numero(i64::MAX),
+            TokenKind::NumeroGrande(BigInt::de_digitos("9223372036854775808").unwrap()),
+            TokenKind::NumeroGrande(BigInt::de_digitos(&digitos).unwrap()),
+        ]
+    );
+    assert_eq!(tokens(&digitos)[0].span.literal, digitos);
+}
+
+#[test]
+fn test_identificadores() {
+    assert_eq!(
+        tipos("preco * qtd_2 - _desconto mod2 mod ação"),
+        vec![
+            TokenKind::Identificador("preco".to_string()),
+            TokenKind::Asterisco,
+            TokenKind::Identificador("qtd_2".to_string()),
+            TokenKind::Menos,
+            TokenKind::Identificador("_desconto".to_string()),
+            TokenKind::Identificador("mod2".to_string()),
+            TokenKind::Mod,
+            TokenKind::Identificador("ação".to_string()),
+        ]
+    );
+    assert_eq!(tokens("2x")[1].span.literal, "x");
+}
+
+#[test]
+fn test_separadores_de_instrucao() {
+    assert_eq!(
+        tipos("x = 1;\r\ny"),
+        vec![
+            TokenKind::Identificador("x".to_string()),
+            TokenKind::Igual,
+            TokenKind::Numero(1),
+            TokenKind::PontoEVirgula,
+            TokenKind::NovaLinha,
+            TokenKind::Identificador("y".to_string()),
+        ]
+    );
+    let tokens = tokenize("1\n 2").unwrap();
+    assert_eq!(tokens[1].kind, TokenKind::NovaLinha);
+    assert_eq!((tokens[1].span.start, tokens[1].span.end), (1, 2));
+}
@@ -0,0 +1,87 @@
+use operations::ast::lexer::TokenKind;
+use operations::ast::operadores::{Associativity, OperatorArity, OperatorTable};
+use operations::ast::parser::{ParseError, Parser};
+use operations::ast::valor::Value;
+use operations::tokenize;
+
+fn arvore(expressao: &str, tabela: &OperatorTable) -> String {
+    Parser::new(tokenize(expressao).unwrap())
+        .com_operadores(tabela.clone())
+        .parse()
+        .unwrap()
+        .raiz()
+        .unwrap()
+        .para_string()
+}
+
+#[test]
+fn test_tabela_padrao() {
+    let tabela = OperatorTable::new();
+    assert_eq!(tabela, OperatorTable::default());
+    assert_eq!(tabela.precedencia(&TokenKind::Asterisco, OperatorArity::Binario), Some(6));
+    assert_eq!(tabela.precedencia(&TokenKind::Menos, OperatorArity::Unario), Some(7));
+    assert_eq!(tabela.associatividade(&TokenKind::Potencia, OperatorArity::Binario), Some(Associativity::Direita));
+    assert_eq!(tabela.associatividade(&TokenKind::Menos, OperatorArity::Binario), Some(Associativity::Esquerda));
+    assert_eq!(tabela.precedencia(&TokenKind::Nao, OperatorArity::Binario), None);
+    // Com a tabela padrão, o parser se comporta como sem tabela alguma
+    let expressao = "-2 ^ 2 * 3 - 1 < 4 == !a || b && c ? 1 : 2";
+    assert_eq!(
+        arvore(expressao, &tabela),
+        Parser::new(tokenize(expressao).unwrap()).parse().unwrap().raiz().unwrap().para_string()
+    );
+}
+
+#[test]
+fn test_menos_unario_acima_da_potencia() {
+    let tabela = OperatorTable::new().com(TokenKind::Menos, OperatorArity::Unario, 9, Associativity::Esquerda);
+    assert_eq!(arvore("-2 ^ 2", &tabela), "(-2 ^ 2)");
+    assert_eq!(arvore("2 ^ -3 ^ 2", &tabela), "(2 ^ (-3 ^ 2))");
+    let ast = Parser::new(tokenize("-2 ^ 2").unwrap()).com_operadores(tabela).parse().unwrap();
+    assert_eq!(ast.avaliar(), Ok(Value::Numero(4)));
+    assert_eq!(Parser::new(tokenize("-2 ^ 2").unwrap()).parse().unwrap().avaliar(), Ok(Value::Numero(-4)));
+}
+
+#[test]
+fn test_precedencia_e_associatividade_personalizadas() {
+    // Potência associativa à esquerda, como em algumas planilhas
+    let tabela = OperatorTable::new().com(TokenKind::Potencia, OperatorArity::Binario, 8, Associativity::Esquerda);
+    assert_eq!(arvore("2 ^ 3 ^ 2", &tabela), "((2 ^ 3) ^ 2)");
+
+    // Todos os aritméticos no mesmo nível, avaliados da esquerda para a direita
+    let mut tabela = OperatorTable::new();
+    for simbolo in [TokenKind::Mais, TokenKind::Menos, TokenKind::Asterisco, TokenKind::Barra] {
+        assert!(tabela.registrar(simbolo, OperatorArity::Binario, 5, Associativity::Esquerda));
+    }
+    assert_eq!(arvore("1 + 2 * 3 - 4 / 2", &tabela), "((((1 + 2) * 3) - 4) / 2)");
+
+    // Um unário associativo à direita absorve os binários de mesma precedência
+    let tabela = OperatorTable::new().com(TokenKind::Nao, OperatorArity::Unario, 3, Associativity::Direita);
+    assert_eq!(arvore("!a == b && c", &tabela), "(!(a == b) && c)");
+    let tabela = OperatorTable::new().com(TokenKind::Nao, OperatorArity::Unario, 3, Associativity::Esquerda);
+    assert_eq!(arvore("!a == b && c", &tabela), "((!a == b) && c)");
+
+    // Precedências altas não transbordam
+    let tabela = OperatorTable::new().com(TokenKind::Potencia, OperatorArity::Binario, u8::MAX, Associativity::Direita);
+    assert_eq!(arvore("2 * 3 ^ 2 ^ 1", &tabela), "(2 * (3 ^ (2 ^ 1)))");
+}
+
+#[test]
+fn test_operadores_removidos() {
+    let mut tabela = OperatorTable::new();
+    assert!(tabela.remover(&TokenKind::Porcento, OperatorArity::Binario));
+    assert!(!tabela.remover(&TokenKind::Porcento, OperatorArity::Binario));
+    let erro = Parser::new(tokenize("7 % 2").unwrap()).com_operadores(tabela).parse().unwrap_err();
+    assert!(matches!(erro, ParseError::TokenInesperado(t) if t.kind == TokenKind::Porcento));
+
+    // Sem o `-` unário, um sinal no início da expressão é um operando ausente
+    let mut tabela = OperatorTable::new();
+    assert!(tabela.remover(&TokenKind::Menos, OperatorArity::Unario));
+    assert_eq!(arvore("3 - 1", &tabela), "(3 - 1)");
+    let erro = Parser::new(tokenize("-1").unwrap()).com_operadores(tabela).parse().unwrap_err();
+    assert!(matches!(erro, ParseError::OperandoAusente(t) if t.kind == TokenKind::Menos));
+
+    // Uma tabela vazia só aceita operandos isolados
+    assert_eq!(arvore("(42)", &OperatorTable::vazia()), "42");
+    let erro = Parser::new(tokenize("1 + 2").unwrap()).com_operadores(OperatorTable::vazia()).parse().unwrap_err();
+    assert!(matches!(erro, ParseError::TokenInesperado(t) if t.kind == TokenKind::Mais));
+}
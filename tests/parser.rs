@@ -0,0 +1,131 @@
+use operations::ast::lexer::{Lexer, TokenKind};
+use operations::ast::parser::{ParseError, Parser, PROFUNDIDADE_MAXIMA};
+use operations::ast::valor::Value;
+use operations::tokenize;
+
+fn arvore(expressao: &str) -> String {
+    Parser::new(tokenize(expressao).unwrap()).parse().unwrap().raiz().unwrap().para_string()
+}
+
+fn erros(expressao: &str) -> Vec<String> {
+    let tokens = Lexer::new(expressao).collect();
+    match Parser::new(tokens).parse_com_recuperacao() {
+        Ok(_) => Vec::new(),
+        Err(erros) => erros.iter().map(ToString::to_string).collect(),
+    }
+}
+
+#[test]
+fn test_associatividade() {
+    assert_eq!(arvore("10 - 4 - 3"), "((10 - 4) - 3)");
+    assert_eq!(arvore("64 / 8 / 2 // 2 mod 3"), "((((64 / 8) / 2) // 2) mod 3)");
+    assert_eq!(arvore("2 ^ 3 ^ 2 ^ 1"), "(2 ^ (3 ^ (2 ^ 1)))");
+    assert_eq!(arvore("a || b || c && d && e"), "((a || b) || ((c && d) && e))");
+    assert_eq!(arvore("a == b != c"), "((a == b) != c)");
+}
+
+#[test]
+fn test_precedencia_entre_niveis() {
+    assert_eq!(
+        arvore("1 + 2 * 3 ^ 2 < 4 - 5 == !a || b && c"),
+        "((((1 + (2 * (3 ^ 2))) < (4 - 5)) == !a) || (b && c))"
+    );
+    assert_eq!(arvore("2 * 3 + 4 * 5"), "((2 * 3) + (4 * 5))");
+    // Sinais ligam mais forte que os operadores binários, exceto a potência
+    assert_eq!(arvore("-2 ^ 2 * -3"), "(-(2 ^ 2) * -3)");
+    assert_eq!(arvore("2 ^ -3 ^ 2"), "(2 ^ -(3 ^ 2))");
+    assert_eq!(arvore("- - 1 + !!a"), "(-(-1) + !!a)");
+    assert_eq!(arvore("((((1))))"), "1");
+}
+
+#[test]
+fn test_aninhamento_profundo() {
+    let profundidade = 100;
+    let expressao = format!("{}1{}", "(".repeat(profundidade), ")".repeat(profundidade));
+    assert_eq!(arvore(&expressao), "1");
+    let soma = vec!["1"; profundidade].join(" + ");
+    assert_eq!(Parser::new(tokenize(&soma).unwrap()).parse().unwrap().avaliar(), Ok(100.into()));
+}
+
+#[test]
+fn test_aninhamento_excessivo() {
+    let analisar = |expressao: &str| Parser::new(tokenize(expressao).unwrap()).parse();
+    // Uma potência com o máximo de níveis ainda cabe
+    let potencias = vec!["1"; PROFUNDIDADE_MAXIMA - 1].join(" ^ ");
+    assert!(analisar(&potencias).is_ok());
+
+    let profundidade = 100_000;
+    let parenteses = format!("{}1{}", "(".repeat(profundidade), ")".repeat(profundidade));
+    let sinais = format!("{}1", "-".repeat(profundidade));
+    let potencias = vec!["2"; profundidade].join("^");
+    let chamadas = format!("{}1{}", "abs(".repeat(profundidade), ")".repeat(profundidade));
+    let somas = format!("{}1", "1 + (".repeat(profundidade));
+    for expressao in [parenteses, sinais, potencias, chamadas, somas] {
+        let erro = analisar(&expressao).unwrap_err();
+        assert!(matches!(erro, ParseError::AninhamentoExcessivo(_)));
+        assert_eq!(erro.to_string(), format!("expressão aninhada demais: mais de {} níveis", PROFUNDIDADE_MAXIMA));
+    }
+    // O erro aponta para o nível que passou do limite, e encerra a análise mesmo com recuperação
+    let expressao = format!("{}1{}", "(".repeat(profundidade), ")".repeat(profundidade));
+    let erros = Parser::new(tokenize(&expressao).unwrap()).parse_com_recuperacao().unwrap_err();
+    assert_eq!(erros.len(), 1);
+    assert_eq!(erros[0].span().start, PROFUNDIDADE_MAXIMA);
+}
+
+#[test]
+fn test_cadeias_longas_nao_contam_como_aninhamento() {
+    let analisar = |expressao: &str| Parser::new(tokenize(expressao).unwrap()).parse().unwrap();
+    let parcelas = 5000;
+    let soma = analisar(&vec!["1"; parcelas].join(" + "));
+    assert_eq!(soma.avaliar(), Ok(Value::Numero(5000)));
+    let texto = soma.raiz().unwrap().para_string();
+    assert!(texto.starts_with(&format!("{}1 + 1) + 1)", "(".repeat(parcelas - 1))));
+    // Operadores diferentes de mesma precedência, ou de precedência maior à direita, continuam a cadeia
+    assert_eq!(analisar(&vec!["2 * 3 - 5"; parcelas].join(" + ")).avaliar(), Ok(Value::Numero(5000)));
+    assert_eq!(analisar(&vec!["1 < 2"; parcelas].join(" && ")).avaliar(), Ok(Value::Booleano(true)));
+    // O rastro também segue a cadeia sem recursão
+    let rastro = analisar(&vec!["1"; 1000].join(" + ")).eval_step();
+    assert_eq!(rastro.passos.len(), 999);
+    let reduzida = analisar(&format!("2{}", " + 1".repeat(998)));
+    assert!(rastro.passos[0].expressao == reduzida.raiz().unwrap().para_string());
+    assert_eq!(rastro.resultado, Ok(Value::Numero(1000)));
+}
+
+#[test]
+fn test_nenhum_token_e_descartado_em_silencio() {
+    // Todo token que não cabe na expressão vira um erro, esteja ele na
+    // posição de um operando ou de um operador
+    for (entrada, token) in [("1 = 2", "="), ("= 1", "="), ("1 entao", "entao"), ("senao 1", "senao"), ("1 :", ":"), (": 1", ":"), ("1 # 2", "#"), ("# 1", "#"), ("1 , 2", ",")] {
+        assert_eq!(erros(entrada)[0], format!("token inesperado '{}'", token));
+    }
+    assert_eq!(
+        erros("1 ) + ) 2"),
+        ["parêntese fechado sem abertura", "parêntese fechado sem abertura"]
+    );
+}
+
+#[test]
+fn test_recuperacao_segue_a_ordem_da_entrada() {
+    assert_eq!(
+        erros("(2 3 + ) * max(1,, 2) 4"),
+        [
+            "token inesperado '3'",
+            "operando ausente junto a ')'",
+            "operando ausente junto a ','",
+            "entrada restante após a expressão: '4'",
+        ]
+    );
+    // Um operando em falta não consome o operador seguinte
+    let erro = Parser::new(tokenize("1 + * 2").unwrap()).parse().unwrap_err();
+    assert!(matches!(erro, ParseError::OperandoAusente(t) if t.kind == TokenKind::Asterisco));
+}
+
+#[test]
+fn test_spans_compostos_guardam_so_as_posicoes() {
+    let entrada = "max(1, -(2 + 3)) * 4";
+    let ast = Parser::new(tokenize(entrada).unwrap()).parse().unwrap();
+    let raiz = ast.raiz().unwrap();
+    assert_eq!((raiz.span().start, raiz.span().end), (0, entrada.len()));
+    assert!(raiz.span().literal.is_empty());
+    assert_eq!(raiz.span().literal(entrada), entrada);
+}
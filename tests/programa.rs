@@ -0,0 +1,183 @@
+mod common;
+
+use common::{parse, parse_erro};
+use operations::ast::ambiente::Environment;
+use operations::ast::avaliador::{EvalError, Evaluator};
+use operations::ast::lexer::TokenKind;
+use operations::ast::parser::{Node, ParseError, Parser, Statement};
+use operations::ast::valor::Value;
+use operations::diagnostico::Diagnostic;
+use operations::numero::racional::Rational;
+use operations::tokenize;
+
+#[test]
+fn test_variaveis_do_ambiente() {
+    let ambiente = Environment::new().com("preco", 30).com("qtd", 3).com("desconto", 15);
+    assert_eq!(parse("preco * qtd - desconto").avaliar_em(&ambiente), Ok(Value::Numero(75)));
+    assert_eq!(parse("-preco + qtd ^ 2").avaliar_em(&ambiente), Ok(Value::Numero(-21)));
+    assert_eq!(parse("(qtd)").avaliar_em(&ambiente), Ok(Value::Numero(3)));
+}
+
+#[test]
+fn test_variavel_vira_no_da_arvore() {
+    let ast = parse("2 * taxa");
+    match ast.raiz().unwrap() {
+        Node::BinaryOp { right, .. } => match right.as_ref() {
+            Node::Variavel { nome, span } => {
+                assert_eq!(nome, "taxa");
+                assert_eq!((span.start, span.end), (4, 8));
+            }
+            outro => panic!("esperava uma variável, obteve {:?}", outro),
+        },
+        outro => panic!("esperava uma operação binária, obteve {:?}", outro),
+    }
+    assert_eq!(ast.raiz().unwrap().para_string(), "(2 * taxa)");
+}
+
+#[test]
+fn test_variavel_indefinida() {
+    let entrada = "preco * qtd";
+    let ambiente = Environment::new().com("preco", 30);
+    let erro = parse(entrada).avaliar_em(&ambiente).unwrap_err();
+    assert_eq!(
+        erro,
+        EvalError::VariavelIndefinida {
+            nome: "qtd".to_string(),
+            span: erro.span().clone(),
+        }
+    );
+    assert_eq!((erro.span().start, erro.span().end), (8, 11));
+
+    let texto = Diagnostic::from(&erro).renderizar(entrada);
+    assert!(texto.starts_with("erro: variável indefinida 'qtd'\n"));
+    assert!(texto.contains("\n  |         ^^^\n"));
+
+    // Sem ambiente, toda variável é indefinida
+    assert!(matches!(parse("x").avaliar(), Err(EvalError::VariavelIndefinida { .. })));
+}
+
+#[test]
+fn test_ambiente_redefine_e_remove() {
+    let mut ambiente = Environment::new();
+    assert_eq!(ambiente.definir("x", 1), None);
+    assert_eq!(ambiente.definir("x", 2), Some(Value::Numero(1)));
+    ambiente.definir("a", 0);
+    assert_eq!(ambiente.nomes(), vec!["a", "x"]);
+    assert_eq!(ambiente.remover("x"), Some(Value::Numero(2)));
+    assert_eq!(ambiente.obter("x"), None);
+}
+
+#[test]
+fn test_rastro_mostra_variaveis_pelo_nome() {
+    let ambiente = Environment::new().com("preco", 30).com("qtd", 3).com("desconto", 15);
+    let rastro = Evaluator::new().rastrear_em(&parse("preco * qtd - -desconto"), &ambiente);
+    assert_eq!(rastro.expressao, "((preco * qtd) - -desconto)");
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(expressoes, vec!["(90 - -desconto)", "105"]);
+    assert_eq!(rastro.passos[0].operandos, vec![30, 3]);
+    assert_eq!(rastro.resultado, Ok(Value::Numero(105)));
+
+    let rastro = Evaluator::new().rastrear_em(&parse("1 + 2 * y"), &ambiente);
+    assert_eq!(rastro.passos.len(), 0);
+    assert!(matches!(rastro.resultado, Err(EvalError::VariavelIndefinida { .. })));
+}
+
+#[test]
+fn test_ambiente_em_outro_dominio() {
+    let ast = Parser::<Rational>::com_dominio(tokenize("preco / qtd").unwrap()).parse().unwrap();
+    let ambiente = Environment::new()
+        .com("preco", Rational::inteiro(10))
+        .com("qtd", Rational::inteiro(4));
+    assert_eq!(ast.avaliar_em(&ambiente), Ok(Value::Numero(Rational::new(5, 2).unwrap())));
+}
+
+#[test]
+fn test_programa_com_atribuicoes() {
+    assert_eq!(parse("x = 3; y = x * 2; y + 1").avaliar(), Ok(Value::Numero(7)));
+    assert_eq!(parse("x = 3\ny = x * 2\ny + 1").avaliar(), Ok(Value::Numero(7)));
+    assert_eq!(parse("x = 2; x = x ^ 10").avaliar(), Ok(Value::Numero(1024)));
+    // Separadores sobrando não criam instruções
+    assert_eq!(parse(";\n\n x = 1;; x + 1;\n").avaliar(), Ok(Value::Numero(2)));
+}
+
+#[test]
+fn test_instrucoes_do_programa() {
+    let ast = parse("taxa = 5; preco * taxa");
+    let instrucoes = ast.instrucoes();
+    assert_eq!(instrucoes.len(), 2);
+    match &instrucoes[0] {
+        Statement::Atribuicao { nome, valor, span } => {
+            assert_eq!(nome, "taxa");
+            assert_eq!(valor.para_string(), "5");
+            assert_eq!((span.start, span.end), (0, 8));
+        }
+        outra => panic!("esperava uma atribuição, obteve {:?}", outra),
+    }
+    assert!(matches!(instrucoes[1], Statement::Expressao(_)));
+    assert_eq!(ast.raiz().unwrap().para_string(), "(preco * taxa)");
+}
+
+#[test]
+fn test_quebra_de_linha_no_meio_da_expressao() {
+    assert_eq!(parse("1 +\n2").avaliar(), Ok(Value::Numero(3)));
+    // Depois de uma expressão completa, a linha seguinte é outra instrução
+    let ast = parse("(1\n+ 2)\n-3");
+    assert_eq!(ast.instrucoes().len(), 2);
+    assert_eq!(ast.avaliar(), Ok(Value::Numero(-3)));
+    assert_eq!(parse("2 * (1\n+ 2)").avaliar(), Ok(Value::Numero(6)));
+}
+
+#[test]
+fn test_executar_grava_no_ambiente() {
+    let mut ambiente = Environment::new().com("preco", 10);
+    assert_eq!(parse("total = preco * 3; total - 1").executar(&mut ambiente), Ok(Value::Numero(29)));
+    assert_eq!(ambiente.obter("total"), Some(&Value::Numero(30)));
+
+    // avaliar_em trabalha sobre uma cópia
+    let ambiente = Environment::new().com("preco", 10);
+    assert_eq!(parse("preco = 1").avaliar_em(&ambiente), Ok(Value::Numero(1)));
+    assert_eq!(ambiente.obter("preco"), Some(&Value::Numero(10)));
+}
+
+#[test]
+fn test_variavel_usada_antes_da_atribuicao() {
+    let erro = parse("y = x + 1; x = 2").avaliar().unwrap_err();
+    assert!(matches!(erro, EvalError::VariavelIndefinida { ref nome, .. } if nome == "x"));
+}
+
+#[test]
+fn test_erros_de_atribuicao() {
+    let erro = parse_erro("1 + x = 3");
+    assert!(matches!(&erro, ParseError::TokenInesperado(token) if token.kind == TokenKind::Igual));
+    let texto = Diagnostic::from(&erro).renderizar("1 + x = 3");
+    assert!(texto.contains("dica: a atribuição deve começar a instrução"));
+
+    let erro = parse_erro("x = ; 2");
+    assert!(matches!(&erro, ParseError::OperandoAusente(token) if token.kind == TokenKind::PontoEVirgula));
+    assert!(matches!(parse_erro("x = 1; y ="), ParseError::OperandoAusente(_)));
+    assert!(matches!(parse_erro(" ; "), ParseError::OperandoAusente(_)));
+
+    let erros = Parser::new(tokenize("x = (1; y = 2 +; z").unwrap())
+        .parse_com_recuperacao()
+        .unwrap_err();
+    assert_eq!(erros.len(), 2);
+    assert!(matches!(erros[0], ParseError::ParentesesDesbalanceados(_)));
+    assert!(matches!(erros[1], ParseError::OperandoAusente(_)));
+}
+
+#[test]
+fn test_rastro_do_programa() {
+    let rastro = Evaluator::new().rastrear(&parse("x = 1 + 2; y = x * 2; y + 1"));
+    assert_eq!(rastro.expressao, "x = (1 + 2); y = (x * 2); (y + 1)");
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(
+        expressoes,
+        vec!["x = 3; y = (x * 2); (y + 1)", "x = 3; y = 6; (y + 1)", "x = 3; y = 6; 7"]
+    );
+    assert_eq!(rastro.passos[1].operandos, vec![3, 2]);
+    assert_eq!(rastro.resultado, Ok(Value::Numero(7)));
+
+    let rastro = parse("x = 4; x").eval_step();
+    assert_eq!(rastro.passos.len(), 0);
+    assert_eq!(rastro.resultado, Ok(Value::Numero(4)));
+}
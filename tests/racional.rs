@@ -0,0 +1,101 @@
+use operations::ast::avaliador::{EvalError, Evaluator, Rounding};
+use operations::ast::parser::Parser;
+use operations::ast::valor::Value;
+use operations::numero::racional::{Rational, RationalFormat};
+use operations::tokenize;
+
+fn fracao(numerador: i64, denominador: i64) -> Rational {
+    Rational::new(numerador, denominador).unwrap()
+}
+
+fn avaliar(expressao: &str) -> Result<Value<Rational>, EvalError> {
+    Parser::<Rational>::com_dominio(tokenize(expressao).unwrap()).parse().unwrap().avaliar()
+}
+
+#[test]
+fn test_normalizacao() {
+    let r = fracao(6, -8);
+    assert_eq!(r.numerador(), -3);
+    assert_eq!(r.denominador(), 4);
+    assert_eq!(fracao(0, -5), Rational::inteiro(0));
+    assert!(Rational::new(1, 0).is_none());
+    assert!(fracao(10, 5).eh_inteiro());
+}
+
+#[test]
+fn test_formatos() {
+    let r = fracao(79, 3);
+    assert_eq!(r.to_string(), "79/3");
+    assert_eq!(r.formatar(RationalFormat::Misto), "26 1/3");
+    assert_eq!(r.formatar(RationalFormat::Decimal(4)), "26.3333");
+    assert_eq!(fracao(-7, 2).formatar(RationalFormat::Misto), "-3 1/2");
+    assert_eq!(fracao(2, 3).formatar(RationalFormat::Decimal(2)), "0.67");
+    assert_eq!(Rational::inteiro(5).formatar(RationalFormat::Misto), "5");
+}
+
+#[test]
+fn test_literais_decimais_sao_exatos() {
+    assert_eq!(Rational::de_decimal("0.1"), Some(fracao(1, 10)));
+    assert_eq!(Rational::de_decimal(".5"), Some(fracao(1, 2)));
+    assert_eq!(Rational::de_decimal("1e-3"), Some(fracao(1, 1000)));
+    assert_eq!(Rational::de_decimal("2.5E+2"), Some(Rational::inteiro(250)));
+    assert_eq!(avaliar("0.1 + 0.2").unwrap(), fracao(3, 10));
+}
+
+#[test]
+fn test_avaliacao_racional() {
+    assert_eq!(avaliar("10 / 3 + 23").unwrap(), fracao(79, 3));
+    assert_eq!(avaliar("(10 / 3 + 23) * (1 - 4)").unwrap(), Rational::inteiro(-79));
+    assert_eq!(avaliar("2 ^ -2").unwrap(), fracao(1, 4));
+    assert_eq!(avaliar("(2 / 3) ^ 3").unwrap(), fracao(8, 27));
+    assert_eq!(avaliar("-7 // 2").unwrap(), Rational::inteiro(-4));
+    assert_eq!(avaliar("7 mod -2").unwrap(), Rational::inteiro(1));
+    assert_eq!(avaliar("7/2 % 1").unwrap(), fracao(1, 2));
+}
+
+#[test]
+fn test_resto_segue_a_convencao() {
+    let avaliar_com = |expressao: &str, arredondamento| {
+        let ast = Parser::<Rational>::com_dominio(tokenize(expressao).unwrap()).parse().unwrap();
+        Evaluator::new()
+            .com_arredondamento(arredondamento)
+            .avaliar(ast.raiz().unwrap())
+            .unwrap()
+    };
+    assert_eq!(avaliar_com("-7 % 2", Rounding::Truncar), Rational::inteiro(-1));
+    assert_eq!(avaliar_com("7 % -2", Rounding::Piso), Rational::inteiro(-1));
+    assert_eq!(avaliar_com("7 % -2", Rounding::Euclides), Rational::inteiro(1));
+}
+
+#[test]
+fn test_erros_racionais() {
+    assert!(matches!(avaliar("1 / (3 - 3)"), Err(EvalError::DivisaoPorZero { .. })));
+    assert!(matches!(avaliar("0 ^ -1"), Err(EvalError::DivisaoPorZero { .. })));
+    assert!(matches!(avaliar("4 ^ (1 / 2)"), Err(EvalError::ExpoenteFracionario { .. })));
+    assert!(matches!(avaliar("9223372036854775807 + 1"), Err(EvalError::Overflow { .. })));
+    // O denominador comum também é verificado
+    assert!(matches!(
+        avaliar("1 / 4294967291 + 1 / 4294967279"),
+        Err(EvalError::Overflow { .. })
+    ));
+}
+
+#[test]
+fn test_rastro_racional() {
+    let ast = Parser::<Rational>::com_dominio(tokenize("10 / 3 + 23").unwrap()).parse().unwrap();
+    let rastro = Evaluator::new().rastrear(&ast);
+    assert_eq!(rastro.expressao, "((10 / 3) + 23)");
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(expressoes, vec!["(10/3 + 23)", "79/3"]);
+    assert_eq!(rastro.passos[1].operandos, vec![fracao(10, 3), Rational::inteiro(23)]);
+    assert_eq!(rastro.resultado.unwrap(), fracao(79, 3));
+
+    let misto = Evaluator::new().com_formato_racional(RationalFormat::Misto).rastrear(&ast);
+    assert_eq!(misto.passos[0].expressao, "(3 1/3 + 23)");
+    assert_eq!(misto.passos[1].expressao, "26 1/3");
+
+    let decimal = Evaluator::new()
+        .com_formato_racional(RationalFormat::Decimal(3))
+        .rastrear(&ast);
+    assert_eq!(decimal.passos[1].expressao, "26.333");
+}
@@ -0,0 +1,176 @@
+mod common;
+
+use common::parse;
+use operations::ast::valor::Value;
+use operations::repl::{ErrorKind, Reply, Session};
+
+fn saida(texto: &str) -> Reply {
+    Reply::Saida(texto.to_string())
+}
+
+#[test]
+fn test_sessao_guarda_variaveis_funcoes_e_ultimo_resultado() {
+    let mut sessao = Session::new();
+    assert_eq!(sessao.processar("x = 4"), saida("4"));
+    assert_eq!(sessao.processar("f(n) = n * x"), saida(""));
+    assert_eq!(sessao.processar("f(3)"), saida("12"));
+    assert_eq!(sessao.processar("ans + 1"), saida("13"));
+    assert_eq!(sessao.processar("_ * 2"), saida("26"));
+    assert_eq!(sessao.processar("ans > 20"), saida("verdadeiro"));
+    assert_eq!(sessao.ambiente().obter("_"), Some(&Value::Booleano(true)));
+    // Linhas vazias e definições não mudam o último resultado
+    assert_eq!(sessao.processar("   "), saida(""));
+    assert_eq!(sessao.processar("g() = 1"), saida(""));
+    assert_eq!(sessao.ambiente().obter("ans"), Some(&Value::Booleano(true)));
+}
+
+#[test]
+fn test_erros_nao_alteram_a_sessao() {
+    let mut sessao = Session::new();
+    sessao.processar("x = 1");
+    let Reply::Erro(ErrorKind::Avaliacao, texto) = sessao.processar("x = 2; y = 1 / 0") else {
+        panic!("esperava um erro de avaliação");
+    };
+    assert!(texto.starts_with("erro: divisão por zero\n"));
+    assert_eq!(sessao.ambiente().obter("x"), Some(&Value::Numero(1)));
+    assert_eq!(sessao.ambiente().obter("ans"), Some(&Value::Numero(1)));
+
+    let Reply::Erro(ErrorKind::Sintatico, texto) = sessao.processar("1 + * 2 3") else {
+        panic!("esperava erros de sintaxe");
+    };
+    assert_eq!(texto.matches("erro: ").count(), 2);
+    // Um caractere inválido torna léxico o erro, mesmo junto de erros de sintaxe
+    assert!(matches!(sessao.processar("1 + # 2 3"), Reply::Erro(ErrorKind::Lexico, _)));
+    assert_eq!(sessao.processar("x + 1"), saida("2"));
+}
+
+#[test]
+fn test_linhas_de_continuacao() {
+    let mut sessao = Session::new();
+    assert_eq!(sessao.processar("max(1,"), Reply::Continuacao);
+    assert!(sessao.aguardando_continuacao());
+    assert_eq!(sessao.processar("  (2 +"), Reply::Continuacao);
+    assert_eq!(sessao.processar("  3))"), saida("5"));
+    assert!(!sessao.aguardando_continuacao());
+    // Um parêntese fechado a mais não espera continuação
+    assert!(matches!(sessao.processar("1)"), Reply::Erro(..)));
+
+    // No fim da entrada, o que ficou pendente é avaliado e reporta o parêntese aberto
+    assert_eq!(sessao.processar("(1 +"), Reply::Continuacao);
+    let Some(Reply::Erro(_, texto)) = sessao.encerrar_continuacao() else {
+        panic!("esperava um erro de sintaxe");
+    };
+    assert!(texto.contains("parêntese aberto sem fechamento"));
+    assert_eq!(sessao.encerrar_continuacao(), None);
+}
+
+#[test]
+fn test_instrucoes_incompletas_continuam() {
+    let mut sessao = Session::new();
+    // Um operador no fim da linha espera o operando, como o parser num só texto
+    assert_eq!(sessao.processar("1 +"), Reply::Continuacao);
+    assert_eq!(sessao.processar("2"), saida("3"));
+    assert_eq!(sessao.processar("x ="), Reply::Continuacao);
+    assert_eq!(sessao.processar(""), Reply::Continuacao);
+    assert_eq!(sessao.processar("  4 *"), Reply::Continuacao);
+    assert_eq!(sessao.processar("  2"), saida("8"));
+    assert_eq!(parse("1 +\n2").avaliar(), Ok(Value::Numero(3)));
+
+    // Uma condicional espera os ramos que faltam
+    assert_eq!(sessao.processar("se x > 0"), Reply::Continuacao);
+    assert_eq!(sessao.processar("entao 1"), Reply::Continuacao);
+    assert_eq!(sessao.processar("senao 2"), saida("1"));
+    assert_eq!(sessao.processar("x < 0 ? 10"), Reply::Continuacao);
+    assert_eq!(sessao.processar(": 20"), saida("20"));
+
+    // Um erro antes do fim é reportado em vez de esperar mais linhas
+    assert!(matches!(sessao.processar("1 2 +"), Reply::Erro(ErrorKind::Sintatico, _)));
+    assert_eq!(sessao.processar(""), saida(""));
+}
+
+#[test]
+fn test_comandos_durante_a_continuacao() {
+    let mut sessao = Session::new();
+    assert_eq!(sessao.processar("(1 +"), Reply::Continuacao);
+    let Reply::Saida(ajuda) = sessao.processar(":help") else {
+        panic!("esperava a ajuda");
+    };
+    assert!(ajuda.contains(":cancel"));
+    // O comando não entra na expressão, que segue aguardando
+    assert!(sessao.aguardando_continuacao());
+    assert_eq!(sessao.processar("2)"), saida("3"));
+
+    assert_eq!(sessao.processar("max(1,"), Reply::Continuacao);
+    assert_eq!(sessao.processar(":cancel"), saida("entrada pendente descartada"));
+    assert!(!sessao.aguardando_continuacao());
+    assert_eq!(sessao.encerrar_continuacao(), None);
+    assert_eq!(sessao.processar(":cancel"), saida(""));
+    assert_eq!(sessao.processar("ans"), saida("3"));
+
+    // Uma linha que começa por `:` mas não é um comando ainda continua o `?`
+    assert_eq!(sessao.processar("(1 > 0 ? 10"), Reply::Continuacao);
+    assert_eq!(sessao.processar("  : 20)"), saida("10"));
+
+    assert_eq!(sessao.processar("(1 +"), Reply::Continuacao);
+    assert_eq!(sessao.processar(":quit"), Reply::Encerrar);
+}
+
+#[test]
+fn test_comandos() {
+    let mut sessao = Session::new();
+    let Reply::Saida(ajuda) = sessao.processar(":help") else {
+        panic!("esperava a ajuda");
+    };
+    assert!(ajuda.contains(":steps on|off"));
+    assert_eq!(sessao.processar(":quit"), Reply::Encerrar);
+    assert_eq!(sessao.processar(":steps talvez"), Reply::Erro(ErrorKind::Comando, "uso: :steps on|off".to_string()));
+    assert!(matches!(sessao.processar(":sair"), Reply::Erro(_, texto) if texto.starts_with("comando desconhecido ':sair'")));
+
+    assert!(matches!(sessao.processar(":ast"), Reply::Erro(..)));
+    assert_eq!(sessao.processar(":ast x = 1 + 2 * 3; x"), saida("x = (1 + (2 * 3))\nx"));
+    assert_eq!(sessao.processar(":tokens 2*a"), saida("Numero(2) 0..1 '2'\nAsterisco 1..2 '*'\nIdentificador(\"a\") 2..3 'a'\nFimDeArquivo 3..3 ''"));
+    // Sem argumento, os comandos usam a última entrada avaliada
+    sessao.processar("-(1)");
+    assert_eq!(sessao.processar(":ast"), saida("-1"));
+    assert!(!sessao.passos());
+}
+
+#[test]
+fn test_passo_a_passo() {
+    let mut sessao = Session::new();
+    assert_eq!(sessao.processar(":steps on"), saida("passo a passo ligado"));
+    assert!(sessao.passos());
+    assert_eq!(sessao.processar("1 + 2 * 3"), saida("(1 + (2 * 3))\n= (1 + 6)\n= 7"));
+    // Sem operações, o resultado aparece depois da expressão
+    sessao.processar("x = 5");
+    assert_eq!(sessao.processar("x"), saida("x\n= 5"));
+    let Reply::Erro(_, texto) = sessao.processar("2 * 3 + 1 / 0") else {
+        panic!("esperava um erro de avaliação");
+    };
+    assert!(texto.starts_with("((2 * 3) + (1 / 0))\n= (6 + (1 / 0))\nerro: divisão por zero"));
+    // Com os passos, o programa é avaliado uma só vez e as atribuições ficam na sessão
+    assert_eq!(sessao.processar("y = x + 1; y * 2"), saida("y = (x + 1); (y * 2)\n= y = 6; (y * 2)\n= y = 6; 12\n= 12"));
+    assert_eq!(sessao.ambiente().obter("y"), Some(&Value::Numero(6)));
+    sessao.processar(":steps off");
+    assert_eq!(sessao.processar("1 + 2 * 3"), saida("7"));
+}
+
+#[test]
+fn test_erros_apontam_a_linha_do_arquivo() {
+    let mut sessao = Session::new().com_passos(false);
+    sessao.definir_arquivo(Some("calc.txt".to_string()));
+    sessao.processar("x = 1");
+    sessao.processar("");
+    assert_eq!(sessao.processar("max(x,"), Reply::Continuacao);
+    let Reply::Erro(ErrorKind::Avaliacao, texto) = sessao.processar("  y)") else {
+        panic!("esperava um erro de avaliação");
+    };
+    assert!(texto.contains(" --> calc.txt:4:3\n  |\n4 |   y)\n"));
+
+    // Sem arquivo, cada entrada é numerada por si só
+    sessao.definir_arquivo(None);
+    let Reply::Erro(_, texto) = sessao.processar("y") else {
+        panic!("esperava um erro de avaliação");
+    };
+    assert!(texto.contains(" --> 1:1\n"));
+}
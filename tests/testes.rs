@@ -1,294 +1,526 @@
-use operations::ast::lexer::{Lexer, TokenKind, Token};
-use operations::ast::parser::{Parser};
-
-fn tokenize(lexer: &mut Lexer, tokens: &mut Vec<Token>) {
-    while let Some(token) = lexer.proximo_token() {
-        if token.kind != TokenKind::EspacoEmBranco {
-            if token.kind == TokenKind::FimDeArquivo {
-                break;
-            }
-            if token.kind == TokenKind::Erro {
-                println!("Token invalido encontrado");
-                return; // Saímos da função se encontramos um token inválido
-            }
-            tokens.push(token);
-        }
-    }
-}
+mod common;
+
+use common::parse_erro;
+use operations::tokenize;
+use operations::ast::avaliador::{EvalError, Evaluator, Rounding};
+use operations::ast::lexer::{TextSpan, TokenKind};
+use operations::ast::parser::{Node, ParseError, Parser};
+use operations::ast::valor::Value;
+use operations::numero::ErroAritmetico;
+
 #[test]
 fn test_case_1() {
-    let mut lexer = Lexer::new("1 + 3");
-    let mut tokens = Vec::new();
-
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("1 + 3").unwrap();
 
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
+    let ast = parser.parse().unwrap();
 
-    let result = ast.eval_step();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 4); // O Resultado esperado eh 4
 }
 
 #[test]
 fn test_case_2() {
-    let mut lexer = Lexer::new("1 + 2 * 3");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("1 + 2 * 3").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 7);
 }
 
 #[test]
 fn test_case_3() {
-    let mut lexer = Lexer::new("4 / 2 + 7");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("4 / 2 + 7").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 9);
 }
 
 #[test]
 fn test_case_4() {
-    let mut lexer = Lexer::new("1 + 2 + 3 * 4");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("1 + 2 + 3 * 4").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 15);
 }
 
 #[test]
 fn test_case_5() {
-    let mut lexer = Lexer::new("(1 + 2 + 3) * 4");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("(1 + 2 + 3) * 4").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 24);
 }
 
 #[test]
 fn test_case_6() {
-    let mut lexer = Lexer::new("(10 / 3 + 23) * (1 - 4)");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("(10 / 3 + 23) * (1 - 4)").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, -78);
 }
 
 #[test]
 fn test_case_7() {
-    let mut lexer = Lexer::new("((1 + 3) * 8 + 1) / 3");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("((1 + 3) * 8 + 1) / 3").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 11);
 }
 
 #[test]
 fn test_case_8() {
-    let mut lexer = Lexer::new("58 - -8 * (58 + 31) - -14");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("58 - -8 * (58 + 31) - -14").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 784);
 }
 
 #[test]
 fn test_case_9() {
-    let mut lexer = Lexer::new("-71 * (-76 * 91 * (10 - 5 - -82) - -79)");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("-71 * (-76 * 91 * (10 - 5 - -82) - -79)").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 42714523);
 }
 
 #[test]
 fn test_case_10() {
-    let mut lexer = Lexer::new("10 * 20 + 3 * 7 + 2 * 3 + 10 / 3 * 4");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("10 * 20 + 3 * 7 + 2 * 3 + 10 / 3 * 4").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 239);
 }
 
 #[test]
 fn test_case_11() {
-    let mut lexer = Lexer::new("(-13 - -73) * (44 - -78 - 77 + 42 - -32)");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("(-13 - -73) * (44 - -78 - 77 + 42 - -32)").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 7140);
 }
 
 #[test]
 fn test_case_12() {
-    let mut lexer = Lexer::new("-29 * 49 + 47 - 29 + 74 - -85 - -27 + 4 - 28");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("-29 * 49 + 47 - 29 + 74 - -85 - -27 + 4 - 28").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, -1241);
 }
 
 #[test]
 fn test_case_13() {
-    let mut lexer = Lexer::new("-74 - -14 + 42 - -4 + -78 + -50 * -35 * -81 + -41");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("-74 - -14 + 42 - -4 + -78 + -50 * -35 * -81 + -41").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, -141883);
 }
 
 #[test]
 fn test_case_14() {
-    let mut lexer = Lexer::new("80 * -18 * (85 * (-46 + -71) - 12 + 26 - 59) + 84");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("80 * -18 * (85 * (-46 + -71) - 12 + 26 - 59) + 84").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 14385684);
 }
 
 #[test]
 fn test_case_15() {
-    let mut lexer = Lexer::new("25 + 38 + 88 + (-6 - -73) * (-83 + (53 + 97) * 14)");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("25 + 38 + 88 + (-6 - -73) * (-83 + (53 + 97) * 14)").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 135290);
 }
 
 #[test]
 fn test_case_16() {
-    let mut lexer = Lexer::new("(84 - 90) * (-8 - 75 + -83 * (56 - -77) + 4 + -94)");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("(84 - 90) * (-8 - 75 + -83 * (56 - -77) + 4 + -94)").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 67272);
 }
 
 #[test]
 fn test_case_17() {
-    let mut lexer = Lexer::new("(54 - -8 - -35 + -68 - -90) * -39 + -43 + -91 * -30");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("(54 - -8 - -35 + -68 - -90) * -39 + -43 + -91 * -30").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, -1954);
 }
 
 #[test]
 fn test_case_18() {
-    let mut lexer = Lexer::new("-13 - -74 + (66 + -57) * -93 * -9 * 77 + 79 - 66 + -53");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("-13 - -74 + (66 + -57) * -93 * -9 * 77 + 79 - 66 + -53").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 580062);
 }
 
 #[test]
 fn test_case_19() {
-    let mut lexer = Lexer::new("(-72 - 50 * -74 + -45) * 92 * 21 * 5 * (-13 - 66 - 18)");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("(-72 - 50 * -74 + -45) * 92 * 21 * 5 * (-13 - 66 - 18)").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, -3357342660);
 }
 
 #[test]
 fn test_case_20() {
-    let mut lexer = Lexer::new("-7 - -37 * (90 + 70) - 30 - -44 + -32 - 56 - -48 - -78");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("-7 - -37 * (90 + 70) - 30 - -44 + -32 - 56 - -48 - -78").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 5965);
 }
 
 #[test]
 fn test_case_21() {
-    let mut lexer = Lexer::new("65 * -83 - -3 + -20 + 24 - 85 * (-24 + -32) * (61 - 20)");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("65 * -83 - -3 + -20 + 24 - 85 * (-24 + -32) * (61 - 20)").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 189772);
 }
 
 #[test]
 fn test_case_22() {
-    let mut lexer = Lexer::new("55 * 48 * -44 - -32 + 1 * -80 * -94 - 74 * -53 + -30 + -61");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("55 * 48 * -44 - -32 + 1 * -80 * -94 - 74 * -53 + -30 + -61").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, -104777);
 }
 
 #[test]
 fn test_case_23() {
-    let mut lexer = Lexer::new("(-82 * (25 + 62 + 3) - -72 + -65 * -32 * (77 + 12) - -95 + 51)");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("(-82 * (25 + 62 + 3) - -72 + -65 * -32 * (77 + 12) - -95 + 51)").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, 177958);
 }
 
 #[test]
 fn test_case_24() {
-    let mut lexer = Lexer::new("(2 - 65 - (-24 + -97) * -5 * -61) * (-41 + 85 * 9 * -92 * (75 - 18))");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("(2 - 65 - (-24 + -97) * -5 * -61) * (-41 + 85 * 9 * -92 * (75 - 18))").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, -147799088242);
 }
 
 #[test]
 fn test_case_25() {
-    let mut lexer = Lexer::new("-20 + -51 + 20 + -68 * -11 + -35 * -14 - 95 - 32 + -52 * -23 - -90 * -42");
-    let mut tokens = Vec::new();
-    tokenize(&mut lexer, &mut tokens);
+    let tokens = tokenize("-20 + -51 + 20 + -68 * -11 + -35 * -14 - 95 - 32 + -52 * -23 - -90 * -42").unwrap();
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    let result = ast.eval_step();
+    let ast = parser.parse().unwrap();
+    let result = ast.eval_step().resultado.unwrap();
     assert_eq!(result, -1524);
 }
+
+
+#[test]
+fn test_erro_operando_ausente_no_fim() {
+    let erro = parse_erro("1 +");
+    assert!(matches!(erro, ParseError::OperandoAusente(_)));
+    assert_eq!(erro.span().start, 3);
+}
+
+#[test]
+fn test_erro_operando_ausente_no_inicio() {
+    let erro = parse_erro("* 3");
+    assert!(matches!(erro, ParseError::OperandoAusente(_)));
+    assert_eq!(erro.span().literal, "*");
+}
+
+#[test]
+fn test_erro_parenteses_desbalanceados() {
+    let erro = parse_erro("(1 + 2");
+    assert!(matches!(erro, ParseError::ParentesesDesbalanceados(_)));
+    assert_eq!(erro.span().start, 0);
+
+    let erro = parse_erro("1 + 2)");
+    assert!(matches!(erro, ParseError::ParentesesDesbalanceados(_)));
+    assert_eq!(erro.span().start, 5);
+}
+
+#[test]
+fn test_erro_entrada_restante() {
+    let erro = parse_erro("1 2");
+    assert!(matches!(erro, ParseError::EntradaRestante(_)));
+    assert_eq!(erro.span().literal, "2");
+}
+
+fn avaliar_erro(expressao: &str) -> EvalError {
+    let tokens = tokenize(expressao).unwrap();
+    let mut parser = Parser::new(tokens);
+    parser.parse().unwrap().avaliar().unwrap_err()
+}
+
+#[test]
+fn test_erro_divisao_por_zero() {
+    let erro = avaliar_erro("2 + (1 / 0)");
+    assert!(matches!(erro, EvalError::DivisaoPorZero { .. }));
+    assert_eq!((erro.span().start, erro.span().end), (4, 11));
+}
+
+#[test]
+fn test_erro_overflow() {
+    let erro = avaliar_erro("9223372036854775807 + 1");
+    assert!(matches!(erro, EvalError::Overflow { .. }));
+    assert_eq!((erro.span().start, erro.span().end), (0, 23));
+}
+
+#[test]
+fn test_rastro_reduz_uma_operacao_por_vez() {
+    let tokens = tokenize("(1 + 1) * (1 + 1)").unwrap();
+    let rastro = Parser::new(tokens).parse().unwrap().eval_step();
+
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(rastro.expressao, "((1 + 1) * (1 + 1))");
+    assert_eq!(expressoes, vec!["(2 * (1 + 1))", "(2 * 2)", "4"]);
+    assert_eq!(rastro.passos[2].operandos, vec![2, 2]);
+    assert_eq!(rastro.resultado, Ok(Value::Numero(4)));
+}
+
+#[test]
+fn test_rastro_interrompido_por_erro() {
+    let tokens = tokenize("(2 * 3) + 1 / 0").unwrap();
+    let rastro = Parser::new(tokens).parse().unwrap().eval_step();
+
+    assert_eq!(rastro.passos.len(), 1);
+    assert_eq!(rastro.passos[0].expressao, "(6 + (1 / 0))");
+    assert!(matches!(rastro.resultado, Err(EvalError::DivisaoPorZero { .. })));
+}
+
+fn avaliar(expressao: &str) -> Value {
+    let tokens = tokenize(expressao).unwrap();
+    Parser::new(tokens).parse().unwrap().avaliar().unwrap()
+}
+
+#[test]
+fn test_menos_unario_e_binario() {
+    assert_eq!(avaliar("3-4"), -1);
+    assert_eq!(avaliar("3 - -4"), 7);
+    assert_eq!(avaliar("2*-3"), -6);
+    assert_eq!(avaliar("-(2+3)"), -5);
+    assert_eq!(avaliar("--5"), 5);
+    assert_eq!(avaliar("-2 * -(1 - 4)"), -6);
+}
+
+#[test]
+fn test_mais_unario() {
+    assert_eq!(avaliar("+3 - +2"), 1);
+    assert_eq!(avaliar("+-+1"), -1);
+}
+
+#[test]
+fn test_rastro_com_sinais() {
+    let tokens = tokenize("-(2 + 3) * --1").unwrap();
+    let rastro = Parser::new(tokens).parse().unwrap().eval_step();
+
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(rastro.expressao, "(-(2 + 3) * 1)");
+    assert_eq!(expressoes, vec!["(-5 * 1)", "-5"]);
+    assert_eq!(rastro.passos[0].resultado, 5);
+
+    let tokens = tokenize("-((1 + 2) * 3)").unwrap();
+    let rastro = Parser::new(tokens).parse().unwrap().eval_step();
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(expressoes, vec!["-(3 * 3)", "-9"]);
+}
+
+#[test]
+fn test_potencia() {
+    assert_eq!(avaliar("2^10"), 1024);
+    assert_eq!(avaliar("2 ** 10"), 1024);
+    assert_eq!(avaliar("3 * 2^2 + 1"), 13);
+    assert_eq!(avaliar("(-2)^3"), -8);
+    assert_eq!(avaliar("1 ^ (10 ^ 10)"), 1);
+    assert_eq!(avaliar("(-1) ^ (10 ^ 10 + 1)"), -1);
+}
+
+#[test]
+fn test_potencia_associativa_a_direita() {
+    assert_eq!(avaliar("2**3**2"), 512);
+    assert_eq!(avaliar("2^3^2"), 512);
+    assert_eq!(avaliar("(2^3)^2"), 64);
+}
+
+#[test]
+fn test_potencia_liga_mais_forte_que_o_sinal() {
+    assert_eq!(avaliar("-2^2"), -4);
+    assert_eq!(avaliar("-3^2 + 10"), 1);
+    assert_eq!(avaliar("2 * -3^2"), -18);
+}
+
+#[test]
+fn test_potencia_erros() {
+    assert!(matches!(avaliar_erro("2 ^ -1"), EvalError::ExpoenteNegativo { .. }));
+    assert!(matches!(avaliar_erro("2 ^ 63"), EvalError::Overflow { .. }));
+}
+
+#[test]
+fn test_rastro_da_potencia() {
+    let tokens = tokenize("2 ^ 3 ^ 2 - 12").unwrap();
+    let rastro = Parser::new(tokens).parse().unwrap().eval_step();
+
+    let expressoes: Vec<&str> = rastro.passos.iter().map(|passo| passo.expressao.as_str()).collect();
+    assert_eq!(rastro.expressao, "((2 ^ (3 ^ 2)) - 12)");
+    assert_eq!(expressoes, vec!["((2 ^ 9) - 12)", "(512 - 12)", "500"]);
+}
+
+fn avaliar_com(expressao: &str, arredondamento: Rounding) -> Value {
+    let ast = Parser::new(tokenize(expressao).unwrap()).parse().unwrap();
+    let avaliador = Evaluator::new().com_arredondamento(arredondamento);
+    avaliador.avaliar(ast.raiz().unwrap()).unwrap()
+}
+
+#[test]
+fn test_divisao_e_resto_por_convencao() {
+    let casos = [
+        ("-7 / 2", [-3, -4, -4]),
+        ("-7 % 2", [-1, 1, 1]),
+        ("7 / -2", [-3, -4, -3]),
+        ("7 % -2", [1, -1, 1]),
+        ("-7 / -2", [3, 3, 4]),
+        ("-7 % -2", [-1, -1, 1]),
+        ("(10 / 3 + 23) * (1 - 4)", [-78, -78, -78]),
+        ("(-10 / 3 + 23) * (1 - 4)", [-60, -57, -57]),
+    ];
+    for (expressao, esperados) in casos {
+        let obtidos = [Rounding::Truncar, Rounding::Piso, Rounding::Euclides]
+            .map(|arredondamento| avaliar_com(expressao, arredondamento));
+        assert_eq!(obtidos, esperados, "{}", expressao);
+    }
+}
+
+#[test]
+fn test_divisao_inteira_e_mod_ignoram_a_convencao() {
+    for arredondamento in [Rounding::Truncar, Rounding::Piso, Rounding::Euclides] {
+        assert_eq!(avaliar_com("-7 // 2", arredondamento), -4);
+        assert_eq!(avaliar_com("7 // -2", arredondamento), -4);
+        assert_eq!(avaliar_com("-7 mod 2", arredondamento), 1);
+        assert_eq!(avaliar_com("7 mod -2", arredondamento), 1);
+        assert_eq!(avaliar_com("-7 mod -2", arredondamento), 1);
+    }
+}
+
+#[test]
+fn test_divisao_do_menor_inteiro_por_menos_um() {
+    // O quociente de i64::MIN por -1 não cabe em i64; o resto é 0
+    let minimo = "(-9223372036854775807 - 1)";
+    for arredondamento in [Rounding::Truncar, Rounding::Piso, Rounding::Euclides] {
+        let avaliador = Evaluator::new().com_arredondamento(arredondamento);
+        let avaliar = |expressao: String| {
+            let ast = Parser::new(tokenize(&expressao).unwrap()).parse().unwrap();
+            avaliador.avaliar(ast.raiz().unwrap())
+        };
+        for operador in ["/", "//"] {
+            let resultado = avaliar(format!("{} {} -1", minimo, operador));
+            assert!(matches!(resultado, Err(EvalError::Overflow { .. })), "{}", operador);
+        }
+        for operador in ["%", "mod"] {
+            assert_eq!(avaliar(format!("{} {} -1", minimo, operador)), Ok(Value::Numero(0)), "{}", operador);
+        }
+    }
+}
+
+#[test]
+fn test_precedencia_de_resto_e_divisao_inteira() {
+    assert_eq!(avaliar("1 + 17 % 5 * 2"), 5);
+    assert_eq!(avaliar("20 // 3 // 2"), 3);
+    assert_eq!(avaliar("2 + 9 mod 4 ^ 2"), 11);
+}
+
+#[test]
+fn test_resto_por_zero() {
+    assert!(matches!(avaliar_erro("5 % 0"), EvalError::DivisaoPorZero { .. }));
+    assert!(matches!(avaliar_erro("5 // (2 - 2)"), EvalError::DivisaoPorZero { .. }));
+    assert!(matches!(avaliar_erro("5 mod 0"), EvalError::DivisaoPorZero { .. }));
+}
+
+fn avaliar_real(expressao: &str) -> Value<f64> {
+    Parser::<f64>::com_dominio(tokenize(expressao).unwrap()).parse().unwrap().avaliar().unwrap()
+}
+
+#[test]
+fn test_avaliacao_real() {
+    assert_eq!(avaliar_real("10 / 4"), 2.5);
+    assert_eq!(avaliar_real("3.5 * 2 + .5"), 7.5);
+    assert_eq!(avaliar_real("1e-3 * 1000"), 1.0);
+    assert_eq!(avaliar_real("2 ^ -1"), 0.5);
+    assert_eq!(avaliar_real("-7.5 // 2"), -4.0);
+    assert_eq!(avaliar_real("-7.5 mod 2"), 0.5);
+    assert_eq!(avaliar_real("(10 / 3 + 23) * (1 - 4)"), -79.0);
+}
+
+#[test]
+fn test_avaliacao_real_erros() {
+    let erro = |expressao: &str| {
+        Parser::<f64>::com_dominio(tokenize(expressao).unwrap()).parse().unwrap().avaliar().unwrap_err()
+    };
+    assert!(matches!(erro("1 / 0.0"), EvalError::DivisaoPorZero { .. }));
+    assert!(matches!(erro("10 ^ 400"), EvalError::Overflow { .. }));
+    assert!(matches!(erro("(-8) ^ 0.5"), EvalError::ResultadoIndefinido { .. }));
+
+    // Um literal além do alcance de f64 é recusado, em vez de virar infinito
+    let tokens = tokenize("1e999 - 1e999").unwrap();
+    assert!(matches!(tokens[0].kind, TokenKind::Decimal(_)));
+    let erro = Parser::<f64>::com_dominio(tokens).parse().unwrap_err();
+    assert!(matches!(erro, ParseError::LiteralInvalido(_, ErroAritmetico::Overflow)));
+    assert_eq!(erro.span().literal, "1e999");
+    assert_eq!(avaliar_real("1e-999"), 0.0);
+}
+
+#[test]
+fn test_exibicao_real_em_notacao_cientifica() {
+    let avaliador = Evaluator::new();
+    let exibir = |expressao: &str| avaliar_real(expressao).exibir(&avaliador);
+    assert_eq!(exibir("1e300 * 2"), "2e300");
+    assert_eq!(exibir("-1 / 4e7"), "-2.5e-8");
+    assert_eq!(exibir("10 / 4"), "2.5");
+    assert_eq!(exibir("2 ^ 53"), "9007199254740992");
+    assert_eq!(exibir("0.0"), "0");
+    // O rastro mostra os valores da mesma forma
+    let ast = Parser::<f64>::com_dominio(tokenize("1e300 + 1e300").unwrap()).parse().unwrap();
+    let rastro = ast.eval_step();
+    assert_eq!(rastro.expressao, "(1e300 + 1e300)");
+    assert_eq!(rastro.passos[0].expressao, "2e300");
+}
+
+#[test]
+fn test_literal_decimal_em_arvore_inteira() {
+    let erro = parse_erro("1 + 2.5");
+    assert!(matches!(erro, ParseError::LiteralInvalido(_, ErroAritmetico::LiteralDecimal)));
+    assert_eq!(erro.span().literal, "2.5");
+}
+
+#[test]
+fn test_arvore_montada_a_mao_profunda() {
+    // A avaliação não recorre à pilha nativa, e percorre árvores de qualquer profundidade
+    let mut no = Node::Numero { valor: 1, span: TextSpan::new(0, 1, "1".to_string()) };
+    for _ in 0..100_001 {
+        no = Node::UnaryOp { op: TokenKind::Menos, operand: Box::new(no), span: TextSpan::new(0, 1, "-".to_string()) };
+    }
+    assert_eq!(Evaluator::new().avaliar(&no), Ok(Value::Numero(-1)));
+    // Descartar a árvore também não recorre à pilha nativa
+    drop(no);
+}